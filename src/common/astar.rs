@@ -0,0 +1,363 @@
+#![allow(dead_code)]
+//! A* Algorithm and friends.
+//!
+//! A `Pathfinder` searches any graph whose nodes are `Eq + Hash + Clone`,
+//! given a function listing each node's neighbors with the cost of stepping
+//! to them and an admissible heuristic estimating the remaining cost to a
+//! goal.  A heuristic of zero makes the search Dijkstra's algorithm.
+//!
+//! Nodes are interned as they are discovered, so the priority queue only
+//! ever holds indices and the node type needs no ordering of its own.
+//!
+//! When every cheapest path matters rather than just one, `find_all_paths_to`
+//! keeps all equal-cost predecessors of each node and returns them as an
+//! `OptimalPaths` graph that can be counted, walked or enumerated.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::hash::Hash;
+use std::ops::Add;
+
+use num::Zero;
+
+/// Bounds shared by the edge costs of every search in this module.
+pub trait Cost: Copy + Ord + Add<Output = Self> + Zero {}
+impl<T> Cost for T where T: Copy + Ord + Add<Output = T> + Zero {}
+
+/// A path from a start node to a goal node, inclusive, with its total cost.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<NodeType, CostType> {
+    pub nodes: Vec<NodeType>,
+    pub cost: CostType,
+}
+
+type NeighborsFn<'a, NodeType, CostType> = Box<dyn Fn(&NodeType) -> Vec<(NodeType, CostType)> + 'a>;
+type HeuristicFn<'a, NodeType, CostType> = Box<dyn Fn(&NodeType) -> CostType + 'a>;
+
+pub struct Pathfinder<'a, NodeType, CostType = u32> {
+    neighbors_fn: NeighborsFn<'a, NodeType, CostType>,
+    heuristic_fn: HeuristicFn<'a, NodeType, CostType>,
+}
+
+impl<'a, NodeType, CostType> Pathfinder<'a, NodeType, CostType>
+where NodeType: Eq + Hash + Clone,
+      CostType: Cost,
+{
+    /// `neighbors_fn` lists the nodes reachable in one step and what each
+    /// step costs.  `heuristic_fn` must never overestimate the cost to reach
+    /// the nearest goal, or the path found may not be the cheapest.
+    pub fn new(neighbors_fn: impl Fn(&NodeType) -> Vec<(NodeType, CostType)> + 'a,
+               heuristic_fn: impl Fn(&NodeType) -> CostType + 'a) -> Self {
+        Pathfinder { neighbors_fn: Box::new(neighbors_fn), heuristic_fn: Box::new(heuristic_fn) }
+    }
+
+    /// A pathfinder with no heuristic, i.e. Dijkstra's algorithm.
+    pub fn without_heuristic(neighbors_fn: impl Fn(&NodeType) -> Vec<(NodeType, CostType)> + 'a) -> Self {
+        Self::new(neighbors_fn, |_| CostType::zero())
+    }
+
+    /// The cheapest path from `from` to `to`, if there is one.
+    pub fn find_path(&self, from: NodeType, to: NodeType) -> Option<Path<NodeType, CostType>> {
+        self.find_path_to([from], |node| *node == to)
+    }
+
+    /// The cheapest path from any of `starts` to any node satisfying
+    /// `is_goal`, if there is one.
+    pub fn find_path_to(&self, starts: impl IntoIterator<Item = NodeType>,
+                        is_goal: impl Fn(&NodeType) -> bool) -> Option<Path<NodeType, CostType>> {
+        let mut nodes: Vec<NodeType> = Vec::new();
+        let mut node_ids: HashMap<NodeType, usize> = HashMap::new();
+        // For node n, `best_known_path_scores[n]` is the cost of the cheapest
+        // path from a start to n found so far (the "G score") and
+        // `path_predecessors[n]` is the node before n on that path.
+        let mut best_known_path_scores: Vec<CostType> = Vec::new();
+        let mut path_predecessors: Vec<Option<usize>> = Vec::new();
+        let mut open_set: BinaryHeap<Reverse<(CostType, usize)>> = BinaryHeap::new();
+
+        for start in starts {
+            if node_ids.contains_key(&start) {
+                continue;
+            }
+            let id = nodes.len();
+            open_set.push(Reverse(((self.heuristic_fn)(&start), id)));
+            node_ids.insert(start.clone(), id);
+            nodes.push(start);
+            best_known_path_scores.push(CostType::zero());
+            path_predecessors.push(None);
+        }
+
+        while let Some(Reverse((f_score, current))) = open_set.pop() {
+            let g_score = best_known_path_scores[current];
+            if f_score > g_score + (self.heuristic_fn)(&nodes[current]) {
+                continue;  // A cheaper path to this node was already expanded.
+            }
+            if is_goal(&nodes[current]) {
+                let mut path = vec![nodes[current].clone()];
+                let mut node = current;
+                while let Some(predecessor) = path_predecessors[node] {
+                    path.push(nodes[predecessor].clone());
+                    node = predecessor;
+                }
+                path.reverse();
+                return Some(Path { nodes: path, cost: g_score });
+            }
+            for (neighbor, step_cost) in (self.neighbors_fn)(&nodes[current]) {
+                let tentative_score = g_score + step_cost;
+                let id = match node_ids.get(&neighbor) {
+                    Some(&id) if tentative_score >= best_known_path_scores[id] => continue,
+                    Some(&id) => {
+                        best_known_path_scores[id] = tentative_score;
+                        path_predecessors[id] = Some(current);
+                        id
+                    }
+                    None => {
+                        let id = nodes.len();
+                        node_ids.insert(neighbor.clone(), id);
+                        nodes.push(neighbor);
+                        best_known_path_scores.push(tentative_score);
+                        path_predecessors.push(Some(current));
+                        id
+                    }
+                };
+                open_set.push(Reverse((tentative_score + (self.heuristic_fn)(&nodes[id]), id)));
+            }
+        }
+        None
+    }
+
+    /// Every cheapest path from any of `starts` to any node satisfying
+    /// `is_goal`.  Edge costs must be positive and the heuristic consistent
+    /// (never decreasing by more than an edge's cost along that edge); no
+    /// heuristic at all is always consistent.
+    pub fn find_all_paths_to(&self, starts: impl IntoIterator<Item = NodeType>,
+                             is_goal: impl Fn(&NodeType) -> bool) -> OptimalPaths<NodeType, CostType> {
+        let mut result = OptimalPaths {
+            nodes: Vec::new(),
+            node_ids: HashMap::new(),
+            costs: Vec::new(),
+            predecessors: Vec::new(),
+            goals: Vec::new(),
+        };
+        let mut open_set: BinaryHeap<Reverse<(CostType, usize)>> = BinaryHeap::new();
+        for start in starts {
+            if !result.node_ids.contains_key(&start) {
+                let id = result.intern(start, CostType::zero());
+                open_set.push(Reverse(((self.heuristic_fn)(&result.nodes[id]), id)));
+            }
+        }
+
+        let mut best_goal_cost: Option<CostType> = None;
+        while let Some(Reverse((f_score, current))) = open_set.pop() {
+            if best_goal_cost.is_some_and(|best| f_score > best) {
+                break;
+            }
+            let g_score = result.costs[current];
+            if f_score > g_score + (self.heuristic_fn)(&result.nodes[current]) {
+                continue;
+            }
+            if is_goal(&result.nodes[current]) {
+                if !result.goals.contains(&current) {
+                    best_goal_cost = Some(g_score);
+                    result.goals.push(current);
+                }
+                // Any path through a goal to another goal costs more.
+                continue;
+            }
+            for (neighbor, step_cost) in (self.neighbors_fn)(&result.nodes[current]) {
+                let tentative_score = g_score + step_cost;
+                let id = match result.node_ids.get(&neighbor) {
+                    None => result.intern(neighbor, tentative_score),
+                    Some(&id) if tentative_score < result.costs[id] => {
+                        result.costs[id] = tentative_score;
+                        result.predecessors[id].clear();
+                        id
+                    }
+                    Some(&id) => {
+                        if tentative_score == result.costs[id]
+                            && !result.predecessors[id].contains(&current) {
+                            result.predecessors[id].push(current);
+                        }
+                        continue;
+                    }
+                };
+                result.predecessors[id].push(current);
+                open_set.push(Reverse((tentative_score + (self.heuristic_fn)(&result.nodes[id]), id)));
+            }
+        }
+        result
+    }
+}
+
+/// The graph of all cheapest paths found by `Pathfinder::find_all_paths_to`:
+/// every node reached, with all of its equal-cost predecessors.
+#[derive(Debug, Clone)]
+pub struct OptimalPaths<NodeType, CostType> {
+    nodes: Vec<NodeType>,
+    node_ids: HashMap<NodeType, usize>,
+    /// The cheapest known cost from a start to each node.
+    costs: Vec<CostType>,
+    /// For each node, every neighbor through which it is reached at that cost.
+    predecessors: Vec<Vec<usize>>,
+    /// The goal nodes reached at the optimal cost.
+    goals: Vec<usize>,
+}
+
+impl<NodeType, CostType> OptimalPaths<NodeType, CostType>
+where NodeType: Eq + Hash + Clone,
+      CostType: Cost,
+{
+    fn intern(&mut self, node: NodeType, cost: CostType) -> usize {
+        let id = self.nodes.len();
+        self.node_ids.insert(node.clone(), id);
+        self.nodes.push(node);
+        self.costs.push(cost);
+        self.predecessors.push(Vec::new());
+        id
+    }
+
+    /// The cost of the cheapest paths, or `None` if no goal is reachable.
+    pub fn cost(&self) -> Option<CostType> {
+        self.goals.first().map(|&goal| self.costs[goal])
+    }
+
+    /// The goals that can be reached at the optimal cost.
+    pub fn goals(&self) -> Vec<&NodeType> {
+        self.goals.iter().map(|&goal| &self.nodes[goal]).collect()
+    }
+
+    /// The cheapest cost from a start to `node`, if the search settled it.
+    pub fn cost_to(&self, node: &NodeType) -> Option<CostType> {
+        self.node_ids.get(node).map(|&id| self.costs[id])
+    }
+
+    /// The ids of every node on some optimal path, each exactly once.
+    fn optimal_ids(&self) -> Vec<usize> {
+        let mut seen: HashSet<usize> = self.goals.iter().copied().collect();
+        let mut stack: Vec<usize> = self.goals.clone();
+        let mut result = Vec::new();
+        while let Some(id) = stack.pop() {
+            result.push(id);
+            for &predecessor in &self.predecessors[id] {
+                if seen.insert(predecessor) {
+                    stack.push(predecessor);
+                }
+            }
+        }
+        result
+    }
+
+    /// Every node that lies on at least one optimal path.
+    pub fn nodes_on_optimal_paths(&self) -> HashSet<NodeType> {
+        self.optimal_ids().into_iter().map(|id| self.nodes[id].clone()).collect()
+    }
+
+    /// Every edge (from, to) that lies on at least one optimal path.
+    pub fn edges_on_optimal_paths(&self) -> HashSet<(NodeType, NodeType)> {
+        self.optimal_ids()
+            .into_iter()
+            .flat_map(|id| self.predecessors[id].iter().map(move |&p| (p, id)))
+            .map(|(from, to)| (self.nodes[from].clone(), self.nodes[to].clone()))
+            .collect()
+    }
+
+    /// The number of distinct optimal paths, from any start to any goal.
+    pub fn count_paths(&self) -> u64 {
+        // With positive edge costs every predecessor is strictly cheaper, so
+        // counting in order of cost sees all of a node's predecessors first.
+        let mut ids = self.optimal_ids();
+        ids.sort_by_key(|&id| self.costs[id]);
+        let mut counts: HashMap<usize, u64> = HashMap::new();
+        for id in ids {
+            let count = if self.predecessors[id].is_empty() {
+                1
+            } else {
+                self.predecessors[id].iter().map(|p| counts[p]).sum()
+            };
+            counts.insert(id, count);
+        }
+        self.goals.iter().map(|goal| counts[goal]).sum()
+    }
+
+    /// Up to `limit` of the optimal paths, each from a start to a goal.
+    pub fn paths(&self, limit: usize) -> Vec<Vec<NodeType>> {
+        let mut result = Vec::new();
+        // Walk backwards from each goal; each stack entry is a partial path
+        // in reverse, ending (so far) at its last element.
+        let mut stack: Vec<Vec<usize>> = self.goals.iter().rev().map(|&goal| vec![goal]).collect();
+        while let Some(partial) = stack.pop() {
+            if result.len() >= limit {
+                break;
+            }
+            let last = *partial.last().unwrap();
+            if self.predecessors[last].is_empty() {
+                result.push(partial.iter().rev().map(|&id| self.nodes[id].clone()).collect());
+                continue;
+            }
+            for &predecessor in self.predecessors[last].iter().rev() {
+                let mut extended = partial.clone();
+                extended.push(predecessor);
+                stack.push(extended);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_trivial() {
+        let dut = Pathfinder::new(
+            move |&this: &u8| vec![(this + 1, 1)],
+            move |&this: &u8| 5u32.saturating_sub(this as u32));
+        let path = dut.find_path(1, 5).unwrap();
+        assert_eq!(path.nodes, [1, 2, 3, 4, 5].to_vec());
+        assert_eq!(path.cost, 4);
+    }
+
+    #[test]
+    fn test_prefers_cheap_detour() {
+        // 0 -> 3 directly costs 10; 0 -> 1 -> 2 -> 3 costs 3.
+        let edges: HashMap<i32, Vec<(i32, i64)>> = HashMap::from([
+            (0, vec![(3, 10), (1, 1)]),
+            (1, vec![(2, 1)]),
+            (2, vec![(3, 1)]),
+        ]);
+        let dut = Pathfinder::without_heuristic(|n| edges.get(n).cloned().unwrap_or_default());
+        let path = dut.find_path(0, 3).unwrap();
+        assert_eq!(path, Path { nodes: vec![0, 1, 2, 3], cost: 3 });
+        assert_eq!(dut.find_path(3, 0), None);
+        let from_either = dut.find_path_to([2, 0], |&n| n == 3).unwrap();
+        assert_eq!(from_either.nodes, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_all_paths() {
+        // Every monotone lattice path from (0, 0) to (2, 2) is optimal, and
+        // there are C(4, 2) = 6 of them.  (3, 0) is reachable but never on
+        // an optimal path.
+        let dut = Pathfinder::without_heuristic(|&(x, y): &(i32, i32)| {
+            [(x + 1, y), (x, y + 1)]
+                .into_iter()
+                .filter(|&(x, y)| (x <= 2 && y <= 2) || (x, y) == (3, 0))
+                .map(|n| (n, 1))
+                .collect()
+        });
+        let all = dut.find_all_paths_to([(0, 0)], |&n| n == (2, 2));
+        assert_eq!(all.cost(), Some(4));
+        assert_eq!(all.goals(), vec![&(2, 2)]);
+        assert_eq!(all.count_paths(), 6);
+        assert_eq!(all.nodes_on_optimal_paths().len(), 9);
+        assert_eq!(all.edges_on_optimal_paths().len(), 12);
+        assert!(!all.nodes_on_optimal_paths().contains(&(3, 0)));
+        let paths = all.paths(4);
+        assert_eq!(paths.len(), 4);
+        assert!(paths.iter().all(|p| p.len() == 5 && p[0] == (0, 0) && p[4] == (2, 2)));
+        assert_eq!(all.paths(100).len(), 6);
+
+        let unreachable = dut.find_all_paths_to([(0, 0)], |&n| n == (5, 5));
+        assert_eq!(unreachable.cost(), None);
+        assert_eq!(unreachable.count_paths(), 0);
+    }
+}
@@ -0,0 +1,295 @@
+#![allow(dead_code)]
+//! Cellular automata over a `Board`.
+//!
+//! An `Automaton` is a sequence of rules; one step applies each rule in turn
+//! to the result of the previous one.  Rules may be Life-like neighbor-count
+//! rules, template rewrites in the style of `Board::transform`, or arbitrary
+//! per-cell functions of the board.
+
+use std::fmt::Debug;
+
+use super::grid_board::{xy_range, Board, Direction, Xy};
+
+/// How a rule sees its own updates within a single application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateMode {
+    /// Every cell is computed from the board as it was before the rule ran.
+    Synchronous,
+    /// Cells are updated in row-major order, and later cells see the new
+    /// values of earlier ones.
+    InPlace,
+}
+
+/// Which cells count as neighbors for neighbor-count rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// The four orthogonally adjacent cells.
+    VonNeumann,
+    /// The eight orthogonally and diagonally adjacent cells.
+    Moore,
+}
+
+impl Neighborhood {
+    pub fn offsets(self) -> Vec<Xy> {
+        match self {
+            Neighborhood::VonNeumann => Direction::ALL.iter().map(|d| d.to_offset()).collect(),
+            Neighborhood::Moore => vec![
+                Xy::new(1, 0),
+                Xy::new(1, 1),
+                Xy::new(0, 1),
+                Xy::new(-1, 1),
+                Xy::new(-1, 0),
+                Xy::new(-1, -1),
+                Xy::new(0, -1),
+                Xy::new(1, -1),
+            ],
+        }
+    }
+
+    /// The number of in-bounds neighbors of `xy` satisfying `pred`.
+    pub fn count<BoardContent>(self,
+                               board: &Board<BoardContent>,
+                               xy: Xy,
+                               pred: impl Fn(BoardContent) -> bool) -> usize
+    where BoardContent: Copy + Debug + PartialEq,
+    {
+        self.offsets()
+            .into_iter()
+            .filter_map(|offset| board.maybe_at(xy + offset))
+            .filter(|&c| pred(c))
+            .count()
+    }
+}
+
+/// A single rewrite of a board, applied once per automaton step.
+pub trait Rule<BoardContent> {
+    fn apply(&self, board: &mut Board<BoardContent>, mode: UpdateMode);
+}
+
+/// Apply a per-cell update function to every cell of `board`.  In synchronous
+/// mode the function sees a snapshot of the board from before the update.
+fn apply_cellwise<BoardContent>(board: &mut Board<BoardContent>,
+                                mode: UpdateMode,
+                                f: impl Fn(&Board<BoardContent>, Xy) -> BoardContent)
+where BoardContent: Copy + Debug + PartialEq,
+{
+    match mode {
+        UpdateMode::Synchronous => {
+            let before = board.clone();
+            for xy in before.all_coords() {
+                board.set_at(xy, f(&before, xy));
+            }
+        }
+        UpdateMode::InPlace => {
+            for xy in board.all_coords() {
+                let new_value = f(board, xy);
+                board.set_at(xy, new_value);
+            }
+        }
+    }
+}
+
+/// A Life-like rule on a two-state board:  a dead cell becomes alive if its
+/// live-neighbor count is in `birth`, and a live cell stays alive only if its
+/// count is in `survive`.  Cells holding neither state are left alone.
+#[derive(Debug, Clone)]
+pub struct LifeRule<BoardContent> {
+    pub alive: BoardContent,
+    pub dead: BoardContent,
+    pub birth: [bool; 9],
+    pub survive: [bool; 9],
+    pub neighborhood: Neighborhood,
+}
+
+impl<BoardContent> LifeRule<BoardContent> {
+    /// Parse a rule in "B3/S23" notation, using the Moore neighborhood.
+    pub fn from_notation(notation: &str, alive: BoardContent, dead: BoardContent) -> Self {
+        let mut birth = [false; 9];
+        let mut survive = [false; 9];
+        for part in notation.split('/') {
+            let mut chars = part.chars();
+            let counts = match chars.next() {
+                Some('B') | Some('b') => &mut birth,
+                Some('S') | Some('s') => &mut survive,
+                _ => panic!("Cannot parse rule `{}`", notation),
+            };
+            for c in chars {
+                let n = c.to_digit(10)
+                    .filter(|&n| n <= 8)
+                    .unwrap_or_else(|| panic!("Bad neighbor count `{}` in rule `{}`", c, notation));
+                counts[n as usize] = true;
+            }
+        }
+        LifeRule { alive, dead, birth, survive, neighborhood: Neighborhood::Moore }
+    }
+
+    pub fn with_neighborhood(self, neighborhood: Neighborhood) -> Self {
+        LifeRule { neighborhood, ..self }
+    }
+}
+
+impl<BoardContent> Rule<BoardContent> for LifeRule<BoardContent>
+where BoardContent: Copy + Debug + PartialEq,
+{
+    fn apply(&self, board: &mut Board<BoardContent>, mode: UpdateMode) {
+        apply_cellwise(board, mode, |b, xy| {
+            let cell = b.at(xy);
+            if cell != self.alive && cell != self.dead {
+                return cell;
+            }
+            let n = self.neighborhood.count(b, xy, |c| c == self.alive);
+            let lives = if cell == self.alive { self.survive[n] } else { self.birth[n] };
+            if lives { self.alive } else { self.dead }
+        });
+    }
+}
+
+/// Replace every occurrence of `template` with `replacement`, as in
+/// `Board::transform`.  In in-place mode the template is tested at each
+/// location in row-major order against the partially rewritten board.
+#[derive(Clone)]
+pub struct RewriteRule<BoardContent> {
+    pub template: Board<Option<BoardContent>>,
+    pub replacement: Board<Option<BoardContent>>,
+}
+
+impl<BoardContent> RewriteRule<BoardContent>
+where BoardContent: Copy + Debug + PartialEq + TryFrom<char, Error: Debug>,
+{
+    /// Build a rule from string templates as accepted by `Board::opt_from_strs`.
+    pub fn from_strs(template: &[&str], replacement: &[&str]) -> Self {
+        RewriteRule {
+            template: Board::opt_from_strs(&template.to_vec()),
+            replacement: Board::opt_from_strs(&replacement.to_vec()),
+        }
+    }
+}
+
+impl<BoardContent> Rule<BoardContent> for RewriteRule<BoardContent>
+where BoardContent: Copy + Debug + Eq,
+{
+    fn apply(&self, board: &mut Board<BoardContent>, mode: UpdateMode) {
+        match mode {
+            UpdateMode::Synchronous => {
+                *board = board.transform(&self.template, &self.replacement);
+            }
+            UpdateMode::InPlace => {
+                let (w, h) = (self.template.width(), self.template.height());
+                if w > board.width() || h > board.height() {
+                    return;
+                }
+                let last_ul = board.dimensions() - self.template.dimensions();
+                for ul in xy_range(Xy::new(0, 0), last_ul + Xy::new(1, 1)) {
+                    let matched = board.view(ul, self.template.dimensions())
+                        .iter()
+                        .all(|(xy, c)| self.template.at(xy).is_none_or(|t| t == c));
+                    if !matched {
+                        continue;
+                    }
+                    for (xy, r) in self.replacement.as_view().iter() {
+                        if let Some(r) = r {
+                            board.set_at(ul + xy, r);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// An arbitrary rule computing each cell's next value from the board.
+pub struct CellRule<F>(pub F);
+
+impl<BoardContent, F> Rule<BoardContent> for CellRule<F>
+where BoardContent: Copy + Debug + PartialEq,
+      F: Fn(&Board<BoardContent>, Xy) -> BoardContent,
+{
+    fn apply(&self, board: &mut Board<BoardContent>, mode: UpdateMode) {
+        apply_cellwise(board, mode, &self.0);
+    }
+}
+
+/// A sequence of rules applied in order at each step.
+pub struct Automaton<BoardContent> {
+    rules: Vec<Box<dyn Rule<BoardContent>>>,
+    mode: UpdateMode,
+}
+
+impl<BoardContent> Automaton<BoardContent>
+where BoardContent: Copy + Debug + PartialEq,
+{
+    pub fn new(rules: Vec<Box<dyn Rule<BoardContent>>>) -> Self {
+        Automaton { rules, mode: UpdateMode::Synchronous }
+    }
+
+    pub fn with_mode(self, mode: UpdateMode) -> Self {
+        Automaton { mode, ..self }
+    }
+
+    /// Apply every rule once, returning the number of cells that changed.
+    /// Counting the changes clones and compares the whole board, so each
+    /// step costs time proportional to the board's area on top of the rules
+    /// themselves, however few cells change.
+    pub fn step(&self, board: &mut Board<BoardContent>) -> usize {
+        let before = board.clone();
+        for rule in &self.rules {
+            rule.apply(board, self.mode);
+        }
+        before.diff(board).len()
+    }
+
+    /// Run `steps` steps, returning the number of cells changed by each.
+    pub fn run(&self, board: &mut Board<BoardContent>, steps: usize) -> Vec<usize> {
+        (0..steps).map(|_| self.step(board)).collect()
+    }
+
+    /// Step until a step changes nothing, returning the number of cells
+    /// changed by each step that changed something.  Gives up with `None`
+    /// after `max_steps` steps, so that oscillators can't run forever.
+    pub fn run_until_fixpoint(&self, board: &mut Board<BoardContent>,
+                              max_steps: usize) -> Option<Vec<usize>> {
+        let mut result = Vec::new();
+        for _ in 0..max_steps {
+            let changed = self.step(board);
+            if changed == 0 {
+                return Some(result);
+            }
+            result.push(changed);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_life_blinker() {
+        let mut b = Board::from_strings(&[".....", "..#..", "..#..", "..#..", "....."]);
+        let life = Automaton::new(vec![Box::new(LifeRule::from_notation("B3/S23", '#', '.'))]);
+        assert_eq!(life.step(&mut b), 4);
+        assert_eq!(b.to_strings(), vec![".....", ".....", ".###.", ".....", "....."]);
+        assert_eq!(life.run(&mut b, 2), vec![4, 4]);
+        assert_eq!(b.to_strings(), vec![".....", ".....", ".###.", ".....", "....."]);
+        // A blinker never settles.
+        assert_eq!(life.run_until_fixpoint(&mut b, 100), None);
+    }
+
+    #[test]
+    fn test_rewrite_modes() {
+        let rule = RewriteRule::<char>::from_strs(&["#."], &["##"]);
+        let mut b = Board::from_strings(&["#...."]);
+        let synchronous = Automaton::new(vec![Box::new(rule.clone())]);
+        assert_eq!(synchronous.step(&mut b), 1);
+        assert_eq!(b.to_strings(), vec!["##..."]);
+        assert_eq!(synchronous.run_until_fixpoint(&mut b, 4), Some(vec![1, 1, 1]));
+
+        let mut b = Board::from_strings(&["#...."]);
+        let in_place = Automaton::new(vec![Box::new(rule)]).with_mode(UpdateMode::InPlace);
+        assert_eq!(in_place.run_until_fixpoint(&mut b, 1), None);
+        let mut b = Board::from_strings(&["#...."]);
+        assert_eq!(in_place.run_until_fixpoint(&mut b, 2), Some(vec![4]));
+        assert_eq!(b.to_strings(), vec!["#####"]);
+    }
+}
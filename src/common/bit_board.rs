@@ -0,0 +1,390 @@
+#![allow(dead_code)]
+//! A bit-packed boolean board.
+//!
+//! `BitBoard` offers the same accessors as `Board<bool>` but stores one bit
+//! per cell, each row packed into whole `u64` words.  Whole-board logic
+//! operations, popcounts and shifts work a word at a time, which makes them
+//! suitable for neighborhood computations on large grids.
+//!
+//! Invariant:  bits past the end of a row in its last word are always zero.
+
+use std::fmt::Display;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use super::grid_board::{Board, Direction, Scalar, Xy};
+
+const WORD_BITS: usize = 64;
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BitBoard {
+    width: usize,
+    height: usize,
+    /// Words per row.
+    stride: usize,
+    words: Vec<u64>,
+}
+
+/// The 64 bits of `row` starting at bit `start`, reading zeros outside it.
+fn bits_at(row: &[u64], start: i64) -> u64 {
+    let word = start.div_euclid(WORD_BITS as i64);
+    let bit = start.rem_euclid(WORD_BITS as i64) as usize;
+    let get = |w: i64| if w >= 0 && (w as usize) < row.len() { row[w as usize] } else { 0 };
+    if bit == 0 {
+        get(word)
+    } else {
+        (get(word) >> bit) | (get(word + 1) << (WORD_BITS - bit))
+    }
+}
+
+/// A mask of bits `[from, to)` within a single word.
+fn bit_range(from: usize, to: usize) -> u64 {
+    debug_assert!(from <= to && to <= WORD_BITS);
+    if to - from == WORD_BITS { !0 } else { ((1u64 << (to - from)) - 1) << from }
+}
+
+impl BitBoard {
+    pub fn new(rows: usize, cols: usize, default_value: bool) -> Self {
+        let stride = cols.div_ceil(WORD_BITS);
+        let mut result = BitBoard {
+            width: cols,
+            height: rows,
+            stride,
+            words: vec![if default_value { !0 } else { 0 }; rows * stride],
+        };
+        result.clear_tails();
+        result
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn dimensions(&self) -> Xy {
+        Xy::new(self.width as Scalar, self.height as Scalar)
+    }
+
+    fn tail_mask(&self) -> u64 {
+        bit_range(0, self.width - (self.stride.max(1) - 1) * WORD_BITS)
+    }
+
+    fn clear_tails(&mut self) {
+        if self.stride == 0 {
+            return;
+        }
+        let mask = self.tail_mask();
+        for row in self.words.chunks_exact_mut(self.stride) {
+            row[self.stride - 1] &= mask;
+        }
+    }
+
+    fn row_words(&self, y: usize) -> &[u64] {
+        &self.words[y * self.stride..(y + 1) * self.stride]
+    }
+
+    fn row_words_mut(&mut self, y: usize) -> &mut [u64] {
+        &mut self.words[y * self.stride..(y + 1) * self.stride]
+    }
+
+    fn locate(&self, xy: Xy) -> (usize, u64) {
+        let (x, y) = (xy[0] as usize, xy[1] as usize);
+        assert!(x < self.width && y < self.height, "{:?} is off the board", xy);
+        (y * self.stride + x / WORD_BITS, 1 << (x % WORD_BITS))
+    }
+
+    pub fn contains(&self, xy: Xy) -> bool {
+        xy[0] >= 0 && xy[0] < self.width as Scalar && xy[1] >= 0 && xy[1] < self.height as Scalar
+    }
+
+    pub fn at(&self, xy: Xy) -> bool {
+        let (word, bit) = self.locate(xy);
+        self.words[word] & bit != 0
+    }
+
+    pub fn maybe_at(&self, xy: Xy) -> Option<bool> {
+        if self.contains(xy) { Some(self.at(xy)) } else { None }
+    }
+
+    pub fn set_at(&mut self, xy: Xy, c: bool) {
+        let (word, bit) = self.locate(xy);
+        if c {
+            self.words[word] |= bit;
+        } else {
+            self.words[word] &= !bit;
+        }
+    }
+
+    pub fn maybe_set_at(&mut self, xy: Xy, c: bool) {
+        if self.contains(xy) {
+            self.set_at(xy, c);
+        }
+    }
+
+    pub fn toggle_at(&mut self, xy: Xy) {
+        let (word, bit) = self.locate(xy);
+        self.words[word] ^= bit;
+    }
+
+    pub fn all_coords(&self) -> Vec<Xy> {
+        let mut result = Vec::with_capacity(self.width * self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                result.push(Xy::new(x as Scalar, y as Scalar));
+            }
+        }
+        result
+    }
+
+    /// The coordinates of every cell equal to `content`, in row-major order.
+    pub fn coords_of(&self, content: bool) -> Vec<Xy> {
+        let mut result = Vec::new();
+        for y in 0..self.height {
+            for (w, &word) in self.row_words(y).iter().enumerate() {
+                let mut bits = if content { word } else { !word };
+                if !content && w == self.stride - 1 {
+                    bits &= self.tail_mask();
+                }
+                while bits != 0 {
+                    let x = w * WORD_BITS + bits.trailing_zeros() as usize;
+                    bits &= bits - 1;
+                    result.push(Xy::new(x as Scalar, y as Scalar));
+                }
+            }
+        }
+        result
+    }
+
+    pub fn row(&self, y: usize) -> Vec<bool> {
+        (0..self.width).map(|x| self.at(Xy::new(x as Scalar, y as Scalar))).collect()
+    }
+
+    /// Apply `f` word-wise to the bits of `[ul, br)`; `f` receives the old word
+    /// and a mask of the bits in the rectangle and returns the new word.
+    fn update_rect_words(&mut self, ul: Xy, br: Xy, f: impl Fn(u64, u64) -> u64) {
+        let (x0, x1) = (ul[0] as usize, br[0] as usize);
+        if x0 >= x1 {
+            return;
+        }
+        assert!(x1 <= self.width && br[1] as usize <= self.height);
+        for y in ul[1] as usize..br[1] as usize {
+            let row = self.row_words_mut(y);
+            for (w, word) in row.iter_mut().enumerate().take(x1.div_ceil(WORD_BITS)).skip(x0 / WORD_BITS) {
+                let from = x0.saturating_sub(w * WORD_BITS);
+                let to = (x1 - w * WORD_BITS).min(WORD_BITS);
+                *word = f(*word, bit_range(from, to));
+            }
+        }
+    }
+
+    pub fn fill_rect(&mut self, ul: Xy, br: Xy, c: bool) {
+        if c {
+            self.update_rect_words(ul, br, |word, mask| word | mask);
+        } else {
+            self.update_rect_words(ul, br, |word, mask| word & !mask);
+        }
+    }
+
+    pub fn toggle_rect(&mut self, ul: Xy, br: Xy) {
+        self.update_rect_words(ul, br, |word, mask| word ^ mask);
+    }
+
+    pub fn update_rect(&mut self, ul: Xy, br: Xy, f: impl Fn(bool) -> bool) {
+        // Only four functions from bool to bool exist; pick the word operation.
+        match (f(false), f(true)) {
+            (false, false) => self.fill_rect(ul, br, false),
+            (true, true) => self.fill_rect(ul, br, true),
+            (true, false) => self.toggle_rect(ul, br),
+            (false, true) => {}
+        }
+    }
+
+    pub fn neighbors8(&self, loc: Xy) -> Vec<bool> {
+        let dirs8 = [
+            Xy::new(1, 0),
+            Xy::new(1, 1),
+            Xy::new(0, 1),
+            Xy::new(-1, 1),
+            Xy::new(-1, 0),
+            Xy::new(-1, -1),
+            Xy::new(0, -1),
+            Xy::new(1, -1),
+        ];
+        dirs8.iter().filter_map(|&offset| self.maybe_at(loc + offset)).collect()
+    }
+
+    /// The number of set cells.
+    pub fn popcount(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn count(&self, target: bool) -> usize {
+        if target { self.popcount() } else { self.width * self.height - self.popcount() }
+    }
+
+    pub fn any(&self) -> bool {
+        self.words.iter().any(|&w| w != 0)
+    }
+
+    /// A copy of this board with every cell moved by `offset`; cells moved off
+    /// the board are lost and vacated cells are cleared.
+    pub fn shifted_by(&self, offset: Xy) -> BitBoard {
+        let mut result = BitBoard::new(self.height, self.width, false);
+        let (dx, dy) = (offset[0], offset[1]);
+        for y in 0..self.height as Scalar {
+            let src_y = y - dy;
+            if src_y < 0 || src_y >= self.height as Scalar {
+                continue;
+            }
+            let src = self.row_words(src_y as usize).to_vec();
+            for (w, word) in result.row_words_mut(y as usize).iter_mut().enumerate() {
+                *word = bits_at(&src, (w * WORD_BITS) as i64 - dx);
+            }
+        }
+        result.clear_tails();
+        result
+    }
+
+    /// A copy of this board with every cell moved one step in `direction`.
+    pub fn shifted(&self, direction: Direction) -> BitBoard {
+        self.shifted_by(direction.to_offset())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Xy, bool)> + '_ {
+        self.all_coords().into_iter().map(|xy| (xy, self.at(xy)))
+    }
+
+    pub fn to_board(&self) -> Board<bool> {
+        Board { board: (0..self.height).map(|y| self.row(y)).collect() }
+    }
+
+    fn assert_same_size(&self, other: &BitBoard) {
+        assert_eq!((self.width, self.height), (other.width, other.height),
+                   "Boards must be the same size");
+    }
+}
+
+impl From<&Board<bool>> for BitBoard {
+    fn from(board: &Board<bool>) -> Self {
+        let mut result = BitBoard::new(board.height(), board.width(), false);
+        for xy in board.coords_of(true) {
+            result.set_at(xy, true);
+        }
+        result
+    }
+}
+
+impl From<&BitBoard> for Board<bool> {
+    fn from(bits: &BitBoard) -> Self {
+        bits.to_board()
+    }
+}
+
+macro_rules! impl_bit_op {
+    ($op:ident, $method:ident, $assign_op:ident, $assign_method:ident, $word_op:tt) => {
+        impl $assign_op<&BitBoard> for BitBoard {
+            fn $assign_method(&mut self, other: &BitBoard) {
+                self.assert_same_size(other);
+                for (a, b) in self.words.iter_mut().zip(&other.words) {
+                    *a = *a $word_op *b;
+                }
+            }
+        }
+
+        impl $op<&BitBoard> for &BitBoard {
+            type Output = BitBoard;
+
+            fn $method(self, other: &BitBoard) -> BitBoard {
+                let mut result = self.clone();
+                $assign_op::$assign_method(&mut result, other);
+                result
+            }
+        }
+    };
+}
+
+impl_bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign, &);
+impl_bit_op!(BitOr, bitor, BitOrAssign, bitor_assign, |);
+impl_bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^);
+
+impl Not for &BitBoard {
+    type Output = BitBoard;
+
+    fn not(self) -> BitBoard {
+        let mut result = self.clone();
+        for word in result.words.iter_mut() {
+            *word = !*word;
+        }
+        result.clear_tails();
+        result
+    }
+}
+
+impl Display for BitBoard {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_board())
+    }
+}
+
+impl std::fmt::Debug for BitBoard {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_board())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bits(rows: &[&str]) -> BitBoard {
+        BitBoard::from(&Board::<bool>::parse_tiles(rows).unwrap())
+    }
+
+    #[test]
+    fn test_round_trip_and_access() {
+        let board: Board<bool> = Board::parse_tiles(&["#..#", ".##."]).unwrap();
+        let packed = BitBoard::from(&board);
+        assert!(packed.to_board() == board);
+        assert_eq!(packed.popcount(), 4);
+        assert_eq!(packed.count(false), 4);
+        assert_eq!(packed.coords_of(false), board.coords_of(false));
+        assert_eq!(packed.maybe_at(Xy::new(4, 0)), None);
+        assert_eq!(packed.to_string(), "#..#\n.##.\n");
+    }
+
+    #[test]
+    fn test_logic() {
+        let a = bits(&["##..", "##.."]);
+        let b = bits(&["#.#.", "#.#."]);
+        assert_eq!(&a & &b, bits(&["#...", "#..."]));
+        assert_eq!(&a | &b, bits(&["###.", "###."]));
+        assert_eq!(&a ^ &b, bits(&[".##.", ".##."]));
+        assert_eq!(!&a, bits(&["..##", "..##"]));
+        assert_eq!((!&a).popcount(), 4);
+    }
+
+    #[test]
+    fn test_shifts_across_words() {
+        let mut wide = BitBoard::new(2, 130, false);
+        wide.set_at(Xy::new(63, 0), true);
+        wide.set_at(Xy::new(129, 1), true);
+        let east = wide.shifted(Direction::East);
+        assert_eq!(east.coords_of(true), vec![Xy::new(64, 0)]);
+        let west = wide.shifted(Direction::West);
+        assert_eq!(west.coords_of(true), vec![Xy::new(62, 0), Xy::new(128, 1)]);
+        let south = wide.shifted(Direction::South);
+        assert_eq!(south.coords_of(true), vec![Xy::new(63, 1)]);
+        assert_eq!(wide.shifted_by(Xy::new(-70, -1)).coords_of(true), vec![Xy::new(59, 0)]);
+    }
+
+    #[test]
+    fn test_rects() {
+        let mut lights = BitBoard::new(3, 200, false);
+        lights.fill_rect(Xy::new(10, 0), Xy::new(150, 2), true);
+        assert_eq!(lights.popcount(), 280);
+        lights.toggle_rect(Xy::new(0, 1), Xy::new(200, 3));
+        assert_eq!(lights.popcount(), 140 + 60 + 200);
+        lights.update_rect(Xy::new(0, 0), Xy::new(200, 3), |c| !c);
+        assert_eq!(lights.popcount(), 200);
+    }
+}
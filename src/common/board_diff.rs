@@ -0,0 +1,203 @@
+#![allow(dead_code)]
+//! Comparing boards, and tracking which cells of a board have changed.
+//!
+//! `Board::diff` lists every cell that differs between two boards.  For
+//! simulations that only need to revisit cells near recent changes, a
+//! `TrackedBoard` records each cell written through it so that the next step
+//! can be limited to the neighborhood of those cells.
+
+use std::fmt::Debug;
+use std::ops::Deref;
+
+use super::grid_board::{Board, Scalar, Xy};
+
+/// A single cell that differs between two boards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellChange<BoardContent> {
+    pub xy: Xy,
+    pub old: BoardContent,
+    pub new: BoardContent,
+}
+
+impl<BoardContent> Board<BoardContent>
+where BoardContent: Copy + Debug + PartialEq,
+{
+    /// Every cell whose value in `newer` differs from its value in `self`, in
+    /// row-major order.  Both boards must be the same size.
+    pub fn diff(&self, newer: &Board<BoardContent>) -> Vec<CellChange<BoardContent>> {
+        assert_eq!(self.dimensions(), newer.dimensions(), "Cannot diff boards of different sizes");
+        let mut result = Vec::new();
+        for (y, (old_row, new_row)) in self.board.iter().zip(&newer.board).enumerate() {
+            for (x, (&old, &new)) in old_row.iter().zip(new_row).enumerate() {
+                if old != new {
+                    result.push(CellChange { xy: Xy::new(x as Scalar, y as Scalar), old, new });
+                }
+            }
+        }
+        result
+    }
+
+    /// Apply a list of changes (e.g. from `diff`) to this board.
+    pub fn apply_changes(&mut self, changes: &[CellChange<BoardContent>]) {
+        for change in changes {
+            self.set_at(change.xy, change.new);
+        }
+    }
+
+    /// Undo a list of changes (e.g. from `diff`) on this board.
+    pub fn revert_changes(&mut self, changes: &[CellChange<BoardContent>]) {
+        for change in changes.iter().rev() {
+            self.set_at(change.xy, change.old);
+        }
+    }
+}
+
+/// A board that remembers which cells have been written since the dirty set
+/// was last taken.  Reads go straight to the underlying board via `Deref`;
+/// writes must go through the tracking setters.
+#[derive(Clone)]
+pub struct TrackedBoard<BoardContent> {
+    board: Board<BoardContent>,
+    is_dirty: Board<bool>,
+    dirty: Vec<Xy>,
+}
+
+impl<BoardContent> TrackedBoard<BoardContent>
+where BoardContent: Copy + Debug + PartialEq,
+{
+    /// Start tracking `board` with no cells dirty.
+    pub fn new(board: Board<BoardContent>) -> Self {
+        let is_dirty = Board::new(board.height(), board.width(), false);
+        TrackedBoard { board, is_dirty, dirty: Vec::new() }
+    }
+
+    /// Start tracking `board` with every cell dirty, so that the first step
+    /// of a simulation visits the whole board.
+    pub fn all_dirty(board: Board<BoardContent>) -> Self {
+        let is_dirty = Board::new(board.height(), board.width(), true);
+        let dirty = board.all_coords();
+        TrackedBoard { board, is_dirty, dirty }
+    }
+
+    fn mark(&mut self, xy: Xy) {
+        if !self.is_dirty.at(xy) {
+            self.is_dirty.set_at(xy, true);
+            self.dirty.push(xy);
+        }
+    }
+
+    /// Set a cell, marking it dirty only if its value actually changes.
+    pub fn set_at(&mut self, xy: Xy, c: BoardContent) {
+        if self.board.at(xy) != c {
+            self.board.set_at(xy, c);
+            self.mark(xy);
+        }
+    }
+
+    pub fn maybe_set_at(&mut self, xy: Xy, c: BoardContent) {
+        if self.board.maybe_at(xy).is_some() {
+            self.set_at(xy, c);
+        }
+    }
+
+    /// Mutable access to a cell, which is marked dirty whether or not the
+    /// caller changes it.
+    pub fn mut_at(&mut self, xy: Xy) -> &mut BoardContent {
+        self.mark(xy);
+        self.board.mut_at(xy)
+    }
+
+    /// The cells written since the dirty set was last taken, in the order
+    /// they were first written.
+    pub fn dirty(&self) -> &[Xy] {
+        &self.dirty
+    }
+
+    pub fn is_dirty(&self, xy: Xy) -> bool {
+        self.is_dirty.at(xy)
+    }
+
+    /// Return the dirty cells and start a fresh dirty set.
+    pub fn take_dirty(&mut self) -> Vec<Xy> {
+        for &xy in &self.dirty {
+            self.is_dirty.set_at(xy, false);
+        }
+        std::mem::take(&mut self.dirty)
+    }
+
+    /// Take the dirty set and expand it to every in-bounds cell within one of
+    /// `offsets` of a dirty cell (include `Xy::new(0, 0)` to keep the dirty
+    /// cells themselves).  This is the set of cells whose neighborhood has
+    /// changed, and so the set a local-rule simulation must revisit.
+    pub fn take_dirty_neighborhood(&mut self, offsets: &[Xy]) -> Vec<Xy> {
+        let dirty = self.take_dirty();
+        let mut seen = Board::new(self.board.height(), self.board.width(), false);
+        let mut result = Vec::new();
+        for xy in dirty {
+            for &offset in offsets {
+                let neighbor = xy + offset;
+                if seen.maybe_at(neighbor) == Some(false) {
+                    seen.set_at(neighbor, true);
+                    result.push(neighbor);
+                }
+            }
+        }
+        result
+    }
+
+    /// Stop tracking and return the underlying board.
+    pub fn into_board(self) -> Board<BoardContent> {
+        self.board
+    }
+}
+
+impl<BoardContent> Deref for TrackedBoard<BoardContent> {
+    type Target = Board<BoardContent>;
+
+    fn deref(&self) -> &Board<BoardContent> {
+        &self.board
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let old: Board<char> = Board::from_strings(&["ab", "cd"]);
+        let new: Board<char> = Board::from_strings(&["xb", "cy"]);
+        let changes = old.diff(&new);
+        assert_eq!(changes, vec![
+            CellChange { xy: Xy::new(0, 0), old: 'a', new: 'x' },
+            CellChange { xy: Xy::new(1, 1), old: 'd', new: 'y' },
+        ]);
+        let mut patched = old.clone();
+        patched.apply_changes(&changes);
+        assert_eq!(patched, new);
+        patched.revert_changes(&changes);
+        assert_eq!(patched, old);
+    }
+
+    #[test]
+    fn test_tracking() {
+        let mut tracked = TrackedBoard::new(Board::<char>::from_strings(&["...", "...", "..."]));
+        tracked.set_at(Xy::new(0, 0), '.');  // Not a change.
+        tracked.set_at(Xy::new(2, 2), '#');
+        *tracked.mut_at(Xy::new(1, 0)) = '#';
+        tracked.set_at(Xy::new(2, 2), '@');
+        assert_eq!(tracked.dirty(), &[Xy::new(2, 2), Xy::new(1, 0)]);
+        assert_eq!(tracked.at(Xy::new(2, 2)), '@');
+
+        let orthogonal = [Xy::new(0, 0), Xy::new(1, 0), Xy::new(-1, 0), Xy::new(0, 1), Xy::new(0, -1)];
+        let mut frontier = tracked.take_dirty_neighborhood(&orthogonal);
+        frontier.sort_by_key(|xy| (xy[1], xy[0]));
+        assert_eq!(frontier, vec![
+            Xy::new(0, 0), Xy::new(1, 0), Xy::new(2, 0),
+            Xy::new(1, 1), Xy::new(2, 1),
+            Xy::new(1, 2), Xy::new(2, 2),
+        ]);
+        assert!(tracked.dirty().is_empty());
+        assert!(!tracked.is_dirty(Xy::new(2, 2)));
+    }
+}
@@ -0,0 +1,296 @@
+#![allow(dead_code)]
+//! Rendering of boards to image files, for inspecting simulations visually.
+//!
+//! Every cell is drawn as a `scale` x `scale` square of the color chosen by a
+//! caller-supplied color map.  Single boards may be written as PPM, PNG or
+//! SVG; sequences of boards as an animated GIF or as numbered frame files.
+
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use super::grid_board::Board;
+
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+
+/// The color map conventionally used for `Board<bool>`:  set cells are dark.
+pub fn black_on_white(cell: bool) -> Rgb {
+    if cell { BLACK } else { WHITE }
+}
+
+/// Image file formats understood by `BoardRenderer::save`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Ppm,
+    Png,
+    Svg,
+}
+
+impl ImageFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Ppm => "ppm",
+            ImageFormat::Png => "png",
+            ImageFormat::Svg => "svg",
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<ImageFormat> {
+        match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+            "ppm" => Some(ImageFormat::Ppm),
+            "png" => Some(ImageFormat::Png),
+            "svg" => Some(ImageFormat::Svg),
+            _ => None,
+        }
+    }
+}
+
+/// Draws boards of `BoardContent` using `color_map` to pick each cell's color.
+pub struct BoardRenderer<F> {
+    color_map: F,
+    scale: usize,
+}
+
+impl<F> BoardRenderer<F> {
+    pub fn new(color_map: F) -> Self {
+        BoardRenderer { color_map, scale: 1 }
+    }
+
+    /// Draw each cell as a `scale` x `scale` pixel square.
+    pub fn with_scale(self, scale: usize) -> Self {
+        assert!(scale > 0);
+        BoardRenderer { scale, ..self }
+    }
+
+    /// The size in pixels of the image of `board`.
+    pub fn image_size<BoardContent>(&self, board: &Board<BoardContent>) -> (usize, usize)
+    where BoardContent: Copy + Debug + PartialEq,
+    {
+        (board.width() * self.scale, board.height() * self.scale)
+    }
+
+    /// Row-major RGB bytes of the image of `board`.
+    pub fn rgb_pixels<BoardContent>(&self, board: &Board<BoardContent>) -> Vec<u8>
+    where BoardContent: Copy + Debug + PartialEq,
+          F: Fn(BoardContent) -> Rgb,
+    {
+        let (w, h) = self.image_size(board);
+        let mut result = Vec::with_capacity(w * h * 3);
+        for row in &board.board {
+            let row_pixels: Vec<u8> = row
+                .iter()
+                .flat_map(|&c| {
+                    let rgb = (self.color_map)(c);
+                    std::iter::repeat_n(rgb, self.scale).flatten()
+                })
+                .collect();
+            for _ in 0..self.scale {
+                result.extend_from_slice(&row_pixels);
+            }
+        }
+        result
+    }
+
+    /// Write `board` as a binary (P6) PPM image.
+    pub fn write_ppm<BoardContent>(&self, board: &Board<BoardContent>, mut out: impl Write)
+        -> io::Result<()>
+    where BoardContent: Copy + Debug + PartialEq,
+          F: Fn(BoardContent) -> Rgb,
+    {
+        let (w, h) = self.image_size(board);
+        write!(out, "P6\n{} {}\n255\n", w, h)?;
+        out.write_all(&self.rgb_pixels(board))
+    }
+
+    /// Write `board` as an 8-bit RGB PNG image.
+    pub fn write_png<BoardContent>(&self, board: &Board<BoardContent>, out: impl Write)
+        -> io::Result<()>
+    where BoardContent: Copy + Debug + PartialEq,
+          F: Fn(BoardContent) -> Rgb,
+    {
+        let (w, h) = self.image_size(board);
+        let mut encoder = png::Encoder::new(out, w as u32, h as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.rgb_pixels(board))?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    /// Write `board` as an SVG image with one `rect` per run of same-colored
+    /// cells in a row.
+    pub fn write_svg<BoardContent>(&self, board: &Board<BoardContent>, mut out: impl Write)
+        -> io::Result<()>
+    where BoardContent: Copy + Debug + PartialEq,
+          F: Fn(BoardContent) -> Rgb,
+    {
+        let (w, h) = self.image_size(board);
+        let s = self.scale;
+        writeln!(out, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" shape-rendering="crispEdges">"#)?;
+        for (y, row) in board.board.iter().enumerate() {
+            let mut x = 0;
+            while x < row.len() {
+                let rgb = (self.color_map)(row[x]);
+                let run = row[x..].iter().take_while(|&&c| (self.color_map)(c) == rgb).count();
+                writeln!(out, r##"<rect x="{}" y="{}" width="{}" height="{}" fill="#{:02x}{:02x}{:02x}"/>"##,
+                         x * s, y * s, run * s, s, rgb[0], rgb[1], rgb[2])?;
+                x += run;
+            }
+        }
+        writeln!(out, "</svg>")
+    }
+
+    /// Write `board` to `path`, choosing the format from its extension.
+    pub fn save<BoardContent>(&self, board: &Board<BoardContent>, path: impl AsRef<Path>)
+        -> io::Result<()>
+    where BoardContent: Copy + Debug + PartialEq,
+          F: Fn(BoardContent) -> Rgb,
+    {
+        let path = path.as_ref();
+        let format = ImageFormat::from_path(path).ok_or_else(|| io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Unknown image format for {}", path.display())))?;
+        let mut out = BufWriter::new(File::create(path)?);
+        match format {
+            ImageFormat::Ppm => self.write_ppm(board, &mut out)?,
+            ImageFormat::Png => self.write_png(board, &mut out)?,
+            ImageFormat::Svg => self.write_svg(board, &mut out)?,
+        }
+        out.flush()
+    }
+
+    /// Write `boards` as numbered frames `{prefix}00000.{ext}`, `{prefix}00001.{ext}`, ...
+    /// returning the paths written.
+    pub fn save_frames<BoardContent>(&self,
+                                     boards: &[Board<BoardContent>],
+                                     prefix: impl AsRef<Path>,
+                                     format: ImageFormat) -> io::Result<Vec<PathBuf>>
+    where BoardContent: Copy + Debug + PartialEq,
+          F: Fn(BoardContent) -> Rgb,
+    {
+        let mut result = Vec::new();
+        for (i, board) in boards.iter().enumerate() {
+            let mut name = prefix.as_ref().as_os_str().to_owned();
+            name.push(format!("{:05}.{}", i, format.extension()));
+            let path = PathBuf::from(name);
+            self.save(board, &path)?;
+            result.push(path);
+        }
+        Ok(result)
+    }
+
+    /// Write `boards` as a looping animated GIF showing each board for
+    /// `frame_delay_cs` hundredths of a second.  All boards must be the same size.
+    pub fn write_gif<BoardContent>(&self,
+                                   boards: &[Board<BoardContent>],
+                                   frame_delay_cs: u16,
+                                   out: impl Write) -> io::Result<()>
+    where BoardContent: Copy + Debug + PartialEq,
+          F: Fn(BoardContent) -> Rgb,
+    {
+        let Some(first) = boards.first() else {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "No frames to write"));
+        };
+        let (w, h) = self.image_size(first);
+        let too_large = |_| io::Error::new(io::ErrorKind::InvalidInput, "Image too large for a GIF");
+        let (gif_w, gif_h) = (u16::try_from(w).map_err(too_large)?, u16::try_from(h).map_err(too_large)?);
+        let frames: Vec<Vec<u8>> = boards.iter().map(|b| {
+            assert_eq!(self.image_size(b), (w, h), "All frames must be the same size");
+            self.rgb_pixels(b)
+        }).collect();
+
+        // Use an exact global palette when the colors fit in one; otherwise
+        // let the encoder quantize each frame.
+        let mut palette: Vec<Rgb> = Vec::new();
+        for rgb in frames.iter().flat_map(|f| f.chunks_exact(3)) {
+            let rgb: Rgb = rgb.try_into().unwrap();
+            if !palette.contains(&rgb) {
+                palette.push(rgb);
+                if palette.len() > 256 { break; }
+            }
+        }
+        let exact = palette.len() <= 256;
+        let global_palette: Vec<u8> = if exact { palette.concat() } else { Vec::new() };
+
+        let gif_error = |e: gif::EncodingError| io::Error::other(e);
+        let mut encoder = gif::Encoder::new(out, gif_w, gif_h, &global_palette)
+            .map_err(gif_error)?;
+        encoder.set_repeat(gif::Repeat::Infinite).map_err(gif_error)?;
+        for pixels in frames {
+            let mut frame = if exact {
+                let indices: Vec<u8> = pixels
+                    .chunks_exact(3)
+                    .map(|rgb| palette.iter().position(|p| p == rgb).unwrap() as u8)
+                    .collect();
+                gif::Frame::from_indexed_pixels(gif_w, gif_h, indices, None)
+            } else {
+                gif::Frame::from_rgb_speed(gif_w, gif_h, &pixels, 10)
+            };
+            frame.delay = frame_delay_cs;
+            encoder.write_frame(&frame).map_err(gif_error)?;
+        }
+        Ok(())
+    }
+
+    /// Write `boards` as an animated GIF at `path`.
+    pub fn save_gif<BoardContent>(&self,
+                                  boards: &[Board<BoardContent>],
+                                  frame_delay_cs: u16,
+                                  path: impl AsRef<Path>) -> io::Result<()>
+    where BoardContent: Copy + Debug + PartialEq,
+          F: Fn(BoardContent) -> Rgb,
+    {
+        let mut out = BufWriter::new(File::create(path)?);
+        self.write_gif(boards, frame_delay_cs, &mut out)?;
+        out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checker() -> Board<bool> {
+        Board { board: vec![vec![true, false], vec![false, true]] }
+    }
+
+    #[test]
+    fn test_ppm() {
+        let mut out = Vec::new();
+        BoardRenderer::new(black_on_white).with_scale(2).write_ppm(&checker(), &mut out).unwrap();
+        let header = b"P6\n4 4\n255\n";
+        assert_eq!(&out[..header.len()], header);
+        assert_eq!(out.len(), header.len() + 4 * 4 * 3);
+        assert_eq!(&out[header.len()..header.len() + 12], &[0, 0, 0, 0, 0, 0, 255, 255, 255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_svg() {
+        let board: Board<char> = Board { board: vec![vec!['a', 'a', 'b']] };
+        let renderer = BoardRenderer::new(|c| if c == 'a' { [255, 0, 0] } else { [0, 0, 255] });
+        let mut out = Vec::new();
+        renderer.write_svg(&board, &mut out).unwrap();
+        let svg = String::from_utf8(out).unwrap();
+        assert!(svg.contains(r##"<rect x="0" y="0" width="2" height="1" fill="#ff0000"/>"##));
+        assert!(svg.contains(r##"<rect x="2" y="0" width="1" height="1" fill="#0000ff"/>"##));
+    }
+
+    #[test]
+    fn test_png_and_gif_signatures() {
+        let renderer = BoardRenderer::new(black_on_white);
+        let mut png_out = Vec::new();
+        renderer.write_png(&checker(), &mut png_out).unwrap();
+        assert_eq!(&png_out[..4], b"\x89PNG");
+        let mut gif_out = Vec::new();
+        renderer.write_gif(&[checker(), checker()], 10, &mut gif_out).unwrap();
+        assert_eq!(&gif_out[..6], b"GIF89a");
+        let huge = BoardRenderer::new(black_on_white).with_scale(40_000);
+        let error = huge.write_gif(&[checker()], 10, &mut Vec::new()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+}
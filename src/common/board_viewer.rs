@@ -0,0 +1,246 @@
+#![allow(dead_code)]
+//! A terminal viewer for stepping through the history of a board simulation.
+//!
+//! A solution records a `Frame` per step (the board plus any cells worth
+//! highlighting) and hands the history to a `BoardViewer`, which draws each
+//! frame with ANSI colors and reads simple commands from its input:
+//!
+//! * `n` or an empty line: step forward
+//! * `p` or `b`: step back
+//! * `g N`: jump to step N
+//! * `play [FPS]`: play from the current step to the end
+//! * `q`: quit
+
+use std::fmt::Debug;
+use std::io::{self, BufRead, Write};
+use std::thread;
+use std::time::Duration;
+
+use super::board_image::Rgb;
+use super::grid_board::{Board, Xy};
+
+const RESET: &str = "\x1b[0m";
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+const HIGHLIGHT_BG: Rgb = [200, 160, 0];
+
+/// How a single cell is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellStyle {
+    pub glyph: char,
+    pub fg: Option<Rgb>,
+    pub bg: Option<Rgb>,
+}
+
+impl CellStyle {
+    pub fn plain(glyph: char) -> Self {
+        CellStyle { glyph, fg: None, bg: None }
+    }
+
+    pub fn colored(glyph: char, fg: Rgb) -> Self {
+        CellStyle { glyph, fg: Some(fg), bg: None }
+    }
+
+    fn write_ansi(&self, out: &mut String) {
+        if let Some([r, g, b]) = self.fg {
+            out.push_str(&format!("\x1b[38;2;{};{};{}m", r, g, b));
+        }
+        if let Some([r, g, b]) = self.bg {
+            out.push_str(&format!("\x1b[48;2;{};{};{}m", r, g, b));
+        }
+        out.push(self.glyph);
+        if self.fg.is_some() || self.bg.is_some() {
+            out.push_str(RESET);
+        }
+    }
+}
+
+/// One recorded step of a simulation.
+#[derive(Clone)]
+pub struct Frame<BoardContent> {
+    pub board: Board<BoardContent>,
+    pub highlights: Vec<Xy>,
+    pub caption: String,
+}
+
+impl<BoardContent> Frame<BoardContent> {
+    pub fn new(board: Board<BoardContent>) -> Self {
+        Frame { board, highlights: Vec::new(), caption: String::new() }
+    }
+
+    pub fn with_highlights(self, highlights: Vec<Xy>) -> Self {
+        Frame { highlights, ..self }
+    }
+
+    pub fn with_caption(self, caption: impl Into<String>) -> Self {
+        Frame { caption: caption.into(), ..self }
+    }
+}
+
+/// Interactive playback of a sequence of frames.
+pub struct BoardViewer<BoardContent, F> {
+    frames: Vec<Frame<BoardContent>>,
+    style: F,
+    current: usize,
+    frames_per_second: f64,
+}
+
+impl<BoardContent, F> BoardViewer<BoardContent, F>
+where BoardContent: Copy + Debug + PartialEq,
+      F: Fn(BoardContent) -> CellStyle,
+{
+    pub fn new(frames: Vec<Frame<BoardContent>>, style: F) -> Self {
+        assert!(!frames.is_empty(), "Nothing to view");
+        BoardViewer { frames, style, current: 0, frames_per_second: 10.0 }
+    }
+
+    /// A viewer for plain boards with no highlights or captions.
+    pub fn from_boards(boards: Vec<Board<BoardContent>>, style: F) -> Self {
+        Self::new(boards.into_iter().map(Frame::new).collect(), style)
+    }
+
+    pub fn with_speed(self, frames_per_second: f64) -> Self {
+        assert!(frames_per_second > 0.0);
+        BoardViewer { frames_per_second, ..self }
+    }
+
+    pub fn current_step(&self) -> usize {
+        self.current
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn step_forward(&mut self) {
+        self.current = (self.current + 1).min(self.frames.len() - 1);
+    }
+
+    pub fn step_back(&mut self) {
+        self.current = self.current.saturating_sub(1);
+    }
+
+    pub fn jump_to(&mut self, step: usize) {
+        self.current = step.min(self.frames.len() - 1);
+    }
+
+    /// The ANSI-colored text of frame `step`, including a status line.
+    pub fn render(&self, step: usize) -> String {
+        let frame = &self.frames[step];
+        let mut result = String::new();
+        for (y, row) in frame.board.board.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                let mut style = (self.style)(cell);
+                if frame.highlights.contains(&Xy::new(x as i64, y as i64)) {
+                    style.bg = Some(HIGHLIGHT_BG);
+                }
+                style.write_ansi(&mut result);
+            }
+            result.push('\n');
+        }
+        result.push_str(&format!("step {}/{}", step, self.frames.len() - 1));
+        if !frame.caption.is_empty() {
+            result.push_str(&format!("  {}", frame.caption));
+        }
+        result.push('\n');
+        result
+    }
+
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "{}{}", CLEAR_SCREEN, self.render(self.current))?;
+        out.flush()
+    }
+
+    /// Play from the current step to the last one, drawing each frame.
+    pub fn play(&mut self, out: &mut impl Write) -> io::Result<()> {
+        let delay = Duration::from_secs_f64(1.0 / self.frames_per_second);
+        loop {
+            self.draw(out)?;
+            if self.current + 1 >= self.frames.len() {
+                return Ok(());
+            }
+            thread::sleep(delay);
+            self.step_forward();
+        }
+    }
+
+    /// Run the interactive loop, reading commands from `input` until it is
+    /// exhausted or a `q` command is read.
+    pub fn run(&mut self, input: impl BufRead, mut out: impl Write) -> io::Result<()> {
+        self.draw(&mut out)?;
+        write!(out, "> ")?;
+        out.flush()?;
+        for line in input.lines() {
+            let line = line?;
+            let mut words = line.split_whitespace();
+            match (words.next(), words.next()) {
+                (None, _) | (Some("n"), _) => self.step_forward(),
+                (Some("p"), _) | (Some("b"), _) => self.step_back(),
+                (Some("g"), Some(n)) => match n.parse() {
+                    Ok(step) => self.jump_to(step),
+                    Err(_) => writeln!(out, "Bad step number `{}`", n)?,
+                },
+                (Some("play"), speed) => {
+                    if let Some(fps) = speed.and_then(|s| s.parse::<f64>().ok()) {
+                        if fps > 0.0 { self.frames_per_second = fps; }
+                    }
+                    self.play(&mut out)?;
+                }
+                (Some("q"), _) => return Ok(()),
+                _ => {
+                    writeln!(out, "Commands: n, p, g N, play [FPS], q")?;
+                    write!(out, "> ")?;
+                    out.flush()?;
+                    continue;
+                }
+            }
+            self.draw(&mut out)?;
+            write!(out, "> ")?;
+            out.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Run the interactive loop on the process's terminal.
+    pub fn run_interactive(&mut self) -> io::Result<()> {
+        self.run(io::stdin().lock(), io::stdout().lock())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style(c: char) -> CellStyle {
+        if c == '#' { CellStyle::colored(c, [255, 0, 0]) } else { CellStyle::plain(c) }
+    }
+
+    fn history() -> Vec<Board<char>> {
+        ["#..", ".#.", "..#"]
+            .iter()
+            .map(|row| Board { board: vec![row.chars().collect()] })
+            .collect()
+    }
+
+    #[test]
+    fn test_render() {
+        let frames = vec![Frame::new(history()[0].clone())
+            .with_highlights(vec![Xy::new(2, 0)])
+            .with_caption("start")];
+        let viewer = BoardViewer::new(frames, style);
+        assert_eq!(viewer.render(0),
+                   "\x1b[38;2;255;0;0m#\x1b[0m.\x1b[48;2;200;160;0m.\x1b[0m\nstep 0/0  start\n");
+    }
+
+    #[test]
+    fn test_commands() {
+        let mut viewer = BoardViewer::from_boards(history(), style).with_speed(1000.0);
+        viewer.run("n\n\np\n".as_bytes(), io::sink()).unwrap();
+        assert_eq!(viewer.current_step(), 1);
+        viewer.run("g 0\nq\nn\n".as_bytes(), io::sink()).unwrap();
+        assert_eq!(viewer.current_step(), 0);
+        viewer.run("g 99\n".as_bytes(), io::sink()).unwrap();
+        assert_eq!(viewer.current_step(), 2);
+        viewer.run("g 0\nplay\n".as_bytes(), io::sink()).unwrap();
+        assert_eq!(viewer.current_step(), 2);
+    }
+}
@@ -0,0 +1,196 @@
+#![allow(dead_code)]
+//! Cycle detection for deterministic simulations.
+//!
+//! A simulation that applies the same transition to a finite state space must
+//! eventually repeat itself:  after a `tail` of distinct states it enters a
+//! loop of `period` states.  Once those are known, the state after any number
+//! of steps, however large, is the state after a small equivalent number.
+//!
+//! Floyd's and Brent's algorithms find the cycle holding only a couple of
+//! states at a time, at the cost of re-running the transition.  The hashed
+//! versions remember every state and so run each step only once; they can
+//! also key the states by some smaller summary, and handle simulations that
+//! end rather than loop.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// The shape of a simulation's trajectory:  states `tail` and
+/// `tail + period` are the first pair of equal states.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    pub tail: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The smallest step number whose state equals the state after `n` steps.
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.tail {
+            n
+        } else {
+            self.tail + (n - self.tail) % self.period
+        }
+    }
+}
+
+/// Find the cycle by Floyd's tortoise and hare.
+pub fn floyd<S: PartialEq + Clone>(start: S, next: impl Fn(&S) -> S) -> Cycle {
+    // The hare runs at twice the tortoise's speed until they meet somewhere
+    // in the loop, at a step that is a multiple of the period.
+    let mut tortoise = next(&start);
+    let mut hare = next(&tortoise);
+    while tortoise != hare {
+        tortoise = next(&tortoise);
+        hare = next(&next(&hare));
+    }
+    // Restarting the tortoise, both at the same speed meet at the loop start.
+    let mut tail = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = next(&tortoise);
+        hare = next(&hare);
+        tail += 1;
+    }
+    let mut period = 1;
+    hare = next(&tortoise);
+    while tortoise != hare {
+        hare = next(&hare);
+        period += 1;
+    }
+    Cycle { tail, period }
+}
+
+/// Find the cycle by Brent's algorithm, which usually needs fewer
+/// transitions than Floyd's.
+pub fn brent<S: PartialEq + Clone>(start: S, next: impl Fn(&S) -> S) -> Cycle {
+    // Find the period by teleporting the tortoise to the hare at each power
+    // of two until the hare comes back to it.
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = start.clone();
+    let mut hare = next(&start);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = next(&hare);
+        period += 1;
+    }
+    // Start the hare one period ahead; they meet at the loop start.
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..period {
+        hare = next(&hare);
+    }
+    let mut tail = 0;
+    while tortoise != hare {
+        tortoise = next(&tortoise);
+        hare = next(&hare);
+        tail += 1;
+    }
+    Cycle { tail, period }
+}
+
+/// The keys of a simulation's states in order, up to either its end or the
+/// first repeated key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trajectory<K> {
+    /// One key per distinct step, starting with the start state's.
+    pub keys: Vec<K>,
+    /// The cycle, if the simulation repeated rather than ending.
+    pub cycle: Option<Cycle>,
+}
+
+/// Run a simulation that may end (`next` returning `None`) until it ends or
+/// a state's `key` repeats.  Keys must determine the rest of the trajectory:
+/// two states with equal keys must have successors with equal keys.
+pub fn run_until_repeat<S, K>(start: S, next: impl Fn(&S) -> Option<S>,
+                              key: impl Fn(&S) -> K) -> Trajectory<K>
+where K: Eq + Hash + Clone,
+{
+    let mut first_seen: HashMap<K, usize> = HashMap::new();
+    let mut keys: Vec<K> = Vec::new();
+    let mut state = Some(start);
+    while let Some(current) = state {
+        let k = key(&current);
+        if let Some(&tail) = first_seen.get(&k) {
+            let period = keys.len() - tail;
+            return Trajectory { keys, cycle: Some(Cycle { tail, period }) };
+        }
+        first_seen.insert(k.clone(), keys.len());
+        keys.push(k);
+        state = next(&current);
+    }
+    Trajectory { keys, cycle: None }
+}
+
+/// Find the cycle by remembering every state.
+pub fn find_cycle<S: Eq + Hash + Clone>(start: S, next: impl Fn(&S) -> S) -> Cycle {
+    run_until_repeat(start, |s| Some(next(s)), S::clone)
+        .cycle
+        .expect("An endless simulation must cycle")
+}
+
+/// The state after `n` steps, skipping whole periods once the simulation is
+/// seen to repeat so that huge `n` cost no more than the tail and one period.
+pub fn state_at<S: Eq + Hash + Clone>(start: S, next: impl Fn(&S) -> S, n: usize) -> S {
+    let mut first_seen: HashMap<S, usize> = HashMap::new();
+    let mut states: Vec<S> = Vec::new();
+    let mut state = start;
+    while states.len() < n {
+        if let Some(&tail) = first_seen.get(&state) {
+            let cycle = Cycle { tail, period: states.len() - tail };
+            return states[cycle.equivalent_step(n)].clone();
+        }
+        first_seen.insert(state.clone(), states.len());
+        let following = next(&state);
+        states.push(state);
+        state = following;
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// x -> x^2 + 1 mod 255 from 3 has a tail of 2 and a period of 6:
+    /// 3, 10, [101, 2, 5, 26, 167, 95], 101, ...
+    fn step(&x: &u32) -> u32 {
+        (x * x + 1) % 255
+    }
+
+    #[test]
+    fn test_detectors_agree() {
+        let expected = Cycle { tail: 2, period: 6 };
+        assert_eq!(floyd(3, step), expected);
+        assert_eq!(brent(3, step), expected);
+        assert_eq!(find_cycle(3, step), expected);
+        // A fixed point is a cycle of period 1.
+        assert_eq!(brent(7, |&x: &u32| (x + 1).min(9)), Cycle { tail: 2, period: 1 });
+        assert_eq!(floyd(0, |&x: &u32| x), Cycle { tail: 0, period: 1 });
+    }
+
+    #[test]
+    fn test_state_at() {
+        let mut expected = 3;
+        for n in 0..40 {
+            assert_eq!(state_at(3, step, n), expected, "step {}", n);
+            expected = step(&expected);
+        }
+        assert_eq!(state_at(3, step, 1_000_000_000_000), state_at(3, step, 2 + 999_999_999_998 % 6));
+        assert_eq!(Cycle { tail: 2, period: 6 }.equivalent_step(9), 3);
+    }
+
+    #[test]
+    fn test_run_until_repeat() {
+        let ends = run_until_repeat(1, |&x| if x < 4 { Some(x + 1) } else { None }, |&x| x);
+        assert_eq!(ends, Trajectory { keys: vec![1, 2, 3, 4], cycle: None });
+        // Keyed on parity only: the second state already repeats a key.
+        let loops = run_until_repeat(1, |&x| Some(x + 2), |&x| x % 2);
+        assert_eq!(loops.cycle, Some(Cycle { tail: 0, period: 1 }));
+    }
+}
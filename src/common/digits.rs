@@ -0,0 +1,196 @@
+#![allow(dead_code)]
+//! Decimal digit arithmetic on integers, without going through strings.
+//!
+//! All of these work on any `num::PrimInt` and assume non-negative values.
+
+use std::ops::RangeInclusive;
+
+use num::PrimInt;
+
+fn ten<T: PrimInt>() -> T {
+    T::from(10).unwrap()
+}
+
+/// `10 ^ k`.  Panics on overflow; see `checked_pow10`.
+pub fn pow10<T: PrimInt>(k: u32) -> T {
+    checked_pow10(k).expect("Power of ten overflowed")
+}
+
+pub fn checked_pow10<T: PrimInt>(k: u32) -> Option<T> {
+    num::checked_pow(ten::<T>(), k as usize)
+}
+
+/// The number of decimal digits of `n`; zero has one digit.
+pub fn digit_count<T: PrimInt>(n: T) -> u32 {
+    assert!(n >= T::zero(), "Digits of a negative number");
+    let mut count = 1;
+    let mut rest = n / ten();
+    while !rest.is_zero() {
+        count += 1;
+        rest = rest / ten();
+    }
+    count
+}
+
+/// Split `n` before its last `k` digits: `split_digits(123456, 2)` is
+/// `(1234, 56)`.
+pub fn split_digits<T: PrimInt>(n: T, k: u32) -> (T, T) {
+    let divisor = pow10::<T>(k);
+    (n / divisor, n % divisor)
+}
+
+/// Split `n` into its high and low halves, if it has an even number of
+/// digits: `split_in_half(253000)` is `Some((253, 0))`.
+pub fn split_in_half<T: PrimInt>(n: T) -> Option<(T, T)> {
+    let count = digit_count(n);
+    if count.is_multiple_of(2) { Some(split_digits(n, count / 2)) } else { None }
+}
+
+/// The digits of `l` followed by the digits of `r`: `concat(12, 345)` is
+/// `12345`.  Panics on overflow; see `checked_concat`.
+pub fn concat<T: PrimInt>(l: T, r: T) -> T {
+    checked_concat(l, r).expect("Concatenation overflowed")
+}
+
+pub fn checked_concat<T: PrimInt>(l: T, r: T) -> Option<T> {
+    l.checked_mul(&checked_pow10(digit_count(r))?)?.checked_add(&r)
+}
+
+/// The digits of `n`, most significant first.
+pub fn digits<T: PrimInt>(n: T) -> impl Iterator<Item = u8> {
+    let mut divisor = pow10::<T>(digit_count(n) - 1);
+    let mut rest = n;
+    std::iter::from_fn(move || {
+        if divisor.is_zero() {
+            return None;
+        }
+        let digit = rest / divisor;
+        rest = rest % divisor;
+        divisor = divisor / ten();
+        digit.to_u8()
+    })
+}
+
+/// The digits of `n`, least significant first.
+pub fn digits_reversed<T: PrimInt>(n: T) -> impl Iterator<Item = u8> {
+    let mut rest = Some(n);
+    std::iter::from_fn(move || {
+        let current = rest?;
+        rest = Some(current / ten()).filter(|r| !r.is_zero());
+        (current % ten()).to_u8()
+    })
+}
+
+/// The number whose digits, most significant first, are `digits`.
+pub fn from_digits<T: PrimInt>(digits: impl IntoIterator<Item = u8>) -> T {
+    digits.into_iter().fold(T::zero(), |acc, d| acc * ten() + T::from(d).unwrap())
+}
+
+/// `block` written out `times` times, assuming it has `block_len` digits:
+/// `repeat_block(12, 2, 3)` is `121212`.  `None` on overflow.
+pub fn repeat_block<T: PrimInt>(block: T, block_len: u32, times: u32) -> Option<T> {
+    block.checked_mul(&repeat_multiplier(block_len, times)?)
+}
+
+/// `1`, `101`, `10101`... : the number that repeats a `block_len` digit
+/// block `times` times when multiplied by it, or `None` if it overflows.
+fn repeat_multiplier<T: PrimInt>(block_len: u32, times: u32) -> Option<T> {
+    let step = checked_pow10::<T>(block_len)?;
+    (1..times).try_fold(T::one(), |acc, _| acc.checked_mul(&step)?.checked_add(&T::one()))
+}
+
+/// Every number in `range` that is some block of digits (without a leading
+/// zero) written out a number of times for which `allow_times` holds, e.g.
+/// `6464` or `123123123`.  Sorted and without duplicates, even for numbers
+/// like `222222` that can be read as repeats in several ways.  This only
+/// visits the repeated numbers, not every number in the range.
+pub fn repeated_block_numbers<T: PrimInt>(range: RangeInclusive<T>,
+                                          allow_times: impl Fn(u32) -> bool) -> Vec<T> {
+    let (lo, hi) = range.into_inner();
+    let mut result = Vec::new();
+    if lo > hi {
+        return result;
+    }
+    for total_len in digit_count(lo)..=digit_count(hi) {
+        for block_len in 1..=total_len / 2 {
+            let times = total_len / block_len;
+            if total_len % block_len != 0 || !allow_times(times) {
+                continue;
+            }
+            // Numbers this long can't be represented; nor can longer ones.
+            let Some(multiplier) = repeat_multiplier::<T>(block_len, times) else { continue };
+            // Blocks must have exactly `block_len` digits, and the repeated
+            // number must be in range.
+            let lowest_block = pow10::<T>(block_len - 1)
+                .max(lo / multiplier + if (lo % multiplier).is_zero() { T::zero() } else { T::one() });
+            let highest_block = (pow10::<T>(block_len) - T::one()).min(hi / multiplier);
+            let mut block = lowest_block;
+            while block <= highest_block {
+                result.push(block * multiplier);
+                block = block + T::one();
+            }
+        }
+    }
+    result.sort();
+    result.dedup();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digits() {
+        assert_eq!(digit_count(0u64), 1);
+        assert_eq!(digit_count(9i64), 1);
+        assert_eq!(digit_count(10usize), 2);
+        assert_eq!(digit_count(u64::MAX), 20);
+        assert_eq!(split_digits(123456u64, 2), (1234, 56));
+        assert_eq!(split_in_half(253000u64), Some((253, 0)));
+        assert_eq!(split_in_half(12345u64), None);
+        assert_eq!(concat(12i64, 345), 12345);
+        assert_eq!(concat(7i64, 0), 70);
+        assert_eq!(checked_concat(u64::MAX, 1), None);
+        assert_eq!(digits(1203u32).collect::<Vec<_>>(), vec![1, 2, 0, 3]);
+        assert_eq!(digits(0u32).collect::<Vec<_>>(), vec![0]);
+        assert_eq!(digits(u64::MAX).count(), 20);
+        assert_eq!(digits_reversed(1203u32).collect::<Vec<_>>(), vec![3, 0, 2, 1]);
+        assert_eq!(from_digits::<u64>(digits(98765u64)), 98765);
+        assert_eq!(repeat_block(12u64, 2, 3), Some(121212));
+        assert_eq!(repeat_block(12u64, 2, 11), None);
+        // At the top of the type, the checked versions give up rather than
+        // panicking.
+        assert_eq!(checked_pow10::<u64>(19), Some(10_000_000_000_000_000_000));
+        assert_eq!(checked_pow10::<u64>(20), None);
+        assert_eq!(checked_concat(1i64, 1_000_000_000_000_000_000), None);
+        assert_eq!(checked_concat(u64::MAX / 10, 5), Some(u64::MAX));
+        assert_eq!(checked_concat(u64::MAX / 10, 6), None);
+    }
+
+    #[test]
+    fn test_repeated_blocks() {
+        // Ranges from the 2025 day 2 example.
+        assert_eq!(repeated_block_numbers(11u64..=22, |t| t == 2), vec![11, 22]);
+        assert_eq!(repeated_block_numbers(95u64..=115, |t| t >= 2), vec![99, 111]);
+        assert_eq!(repeated_block_numbers(998u64..=1012, |t| t >= 2), vec![999, 1010]);
+        assert_eq!(repeated_block_numbers(1188511880u64..=1188511890, |t| t == 2),
+                   vec![1188511885]);
+        assert_eq!(repeated_block_numbers(222220u64..=222224, |t| t >= 2), vec![222222]);
+        assert!(repeated_block_numbers(1698522u64..=1698528, |t| t >= 2).is_empty());
+        // Ranges at the top of the type, where 10 ^ digits overflows.
+        assert_eq!(repeated_block_numbers(u64::MAX - 10..=u64::MAX, |t| t >= 2), vec![]);
+        assert_eq!(repeated_block_numbers(9_999_999_999_999_999_990u64..=10_000_000_000_000_000_010,
+                                          |t| t >= 2),
+                   vec![9_999_999_999_999_999_999]);
+        assert_eq!(repeated_block_numbers(250u8..=255, |t| t >= 2), vec![]);
+        // Matches a brute-force scan.
+        let brute: Vec<u64> = (1..=20000u64)
+            .filter(|&n| {
+                let s = n.to_string();
+                (1..s.len()).any(|k| s.len() % k == 0 && s == s[..k].repeat(s.len() / k))
+            })
+            .collect();
+        assert_eq!(repeated_block_numbers(1u64..=20000, |t| t >= 2), brute);
+    }
+}
@@ -0,0 +1,294 @@
+#![allow(dead_code)]
+//! Inserting operators between numbers to reach a target.
+//!
+//! Given operands `a b c ...` and a target, an `ExpressionSolver` looks for
+//! operators from its set to put between them so that the expression
+//! evaluates to the target, and returns the expression it found.  There are
+//! `ops ^ (n - 1)` candidate expressions, so rather than trying each one it
+//! searches depth first and abandons partial expressions that can no
+//! longer work:
+//!
+//! * Evaluated left to right, with every operand positive, the search runs
+//!   backwards from the target: the last operation must be undoable (the
+//!   target is a multiple of the last operand for `*`, ends in its digits
+//!   for `||`, is larger than it for `+`) and what is left is the target
+//!   for the remaining operands.
+//! * Otherwise it runs forwards, and with every operand positive it stops
+//!   as soon as the running value exceeds the target, since none of the
+//!   operators can make it smaller again.
+//!
+//! Arithmetic is checked; expressions that overflow an `i64` are treated as
+//! not reaching the target.
+
+use std::fmt;
+
+use super::digits::{checked_concat, checked_pow10, digit_count};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    Add,
+    Mul,
+    /// Digit concatenation, `12 || 345 = 12345`.  Only meaningful for
+    /// non-negative operands.
+    Concat,
+}
+
+impl Op {
+    pub const ALL: [Op; 3] = [Op::Add, Op::Mul, Op::Concat];
+
+    pub fn apply(&self, l: i64, r: i64) -> Option<i64> {
+        match self {
+            Op::Add => l.checked_add(r),
+            Op::Mul => l.checked_mul(r),
+            Op::Concat => checked_concat(l, r),
+        }
+    }
+
+    /// The `l` with `l op r = result`, for positive `r`, if there is one.
+    fn undo(&self, result: i64, r: i64) -> Option<i64> {
+        match self {
+            Op::Add => result.checked_sub(r).filter(|&l| l >= 0),
+            Op::Mul => Some(result / r).filter(|_| result % r == 0),
+            Op::Concat => {
+                // A shift too large for an i64 means `result` is shorter
+                // than `r`, so it can't end with it.
+                let shift = checked_pow10::<i64>(digit_count(r))?;
+                Some(result / shift).filter(|_| result % shift == r)
+            }
+        }
+    }
+
+    /// Under `Evaluation::Precedence`, operators with higher precedence bind
+    /// tighter.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            Op::Add => 1,
+            Op::Mul => 2,
+            Op::Concat => 3,
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Op::Add => "+",
+            Op::Mul => "*",
+            Op::Concat => "||",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Evaluation {
+    /// Every operator applies to the result so far: `2 + 3 * 4 = 20`.
+    LeftToRight,
+    /// The usual rules, `||` before `*` before `+`: `2 + 3 * 4 = 14`.
+    Precedence,
+}
+
+/// Operands with the operators between them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Expression {
+    pub operands: Vec<i64>,
+    pub ops: Vec<Op>,
+}
+
+impl Expression {
+    /// The value of the expression, or `None` if it overflows.
+    pub fn evaluate(&self, evaluation: Evaluation) -> Option<i64> {
+        let (first, rest) = self.operands.split_first()?;
+        let mut state = Partial::start(*first);
+        for (op, &operand) in self.ops.iter().zip(rest) {
+            state = state.push(*op, operand, evaluation)?;
+        }
+        state.value()
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, operand) in self.operands.iter().enumerate() {
+            if i > 0 {
+                write!(f, " {} ", self.ops[i - 1].symbol())?;
+            }
+            write!(f, "{}", operand)?;
+        }
+        Ok(())
+    }
+}
+
+/// A partly evaluated expression.  Left to right, only `chunk` is used.
+/// With precedence, the value is `sum + product * chunk`, where `sum` holds
+/// the finished `+` terms, `product` the finished factors of the current
+/// term and `chunk` the number being concatenated onto.
+#[derive(Debug, Clone, Copy)]
+struct Partial {
+    sum: i64,
+    product: i64,
+    chunk: i64,
+}
+
+impl Partial {
+    fn start(operand: i64) -> Self {
+        Partial { sum: 0, product: 1, chunk: operand }
+    }
+
+    fn push(self, op: Op, operand: i64, evaluation: Evaluation) -> Option<Self> {
+        if evaluation == Evaluation::LeftToRight {
+            return Some(Partial { chunk: op.apply(self.chunk, operand)?, ..self });
+        }
+        Some(match op {
+            Op::Add => Partial { sum: self.value()?, product: 1, chunk: operand },
+            Op::Mul => Partial { product: self.product.checked_mul(self.chunk)?, chunk: operand, ..self },
+            Op::Concat => Partial { chunk: checked_concat(self.chunk, operand)?, ..self },
+        })
+    }
+
+    fn value(&self) -> Option<i64> {
+        self.sum.checked_add(self.product.checked_mul(self.chunk)?)
+    }
+}
+
+pub struct ExpressionSolver {
+    ops: Vec<Op>,
+    evaluation: Evaluation,
+}
+
+impl ExpressionSolver {
+    pub fn new(ops: &[Op], evaluation: Evaluation) -> Self {
+        ExpressionSolver { ops: ops.to_vec(), evaluation }
+    }
+
+    /// An expression over `operands`, in order, that evaluates to `target`,
+    /// if there is one.
+    pub fn solve(&self, operands: &[i64], target: i64) -> Option<Expression> {
+        let (&first, rest) = operands.split_first()?;
+        let positive = operands.iter().all(|&o| o > 0);
+        let mut ops = Vec::with_capacity(rest.len());
+        let found = if positive && self.evaluation == Evaluation::LeftToRight {
+            self.backward(operands, target, &mut ops)
+        } else {
+            self.forward(rest, target, Partial::start(first), positive, &mut ops)
+        };
+        if !found {
+            return None;
+        }
+        if positive && self.evaluation == Evaluation::LeftToRight {
+            ops.reverse();
+        }
+        Some(Expression { operands: operands.to_vec(), ops })
+    }
+
+    pub fn is_solvable(&self, operands: &[i64], target: i64) -> bool {
+        self.solve(operands, target).is_some()
+    }
+
+    /// Whether `operands` can make `target`, pushing the operators used onto
+    /// `ops` last first.
+    fn backward(&self, operands: &[i64], target: i64, ops: &mut Vec<Op>) -> bool {
+        let (&last, rest) = operands.split_last().unwrap();
+        if rest.is_empty() {
+            return last == target;
+        }
+        for &op in &self.ops {
+            if let Some(previous) = op.undo(target, last) {
+                ops.push(op);
+                if self.backward(rest, previous, ops) {
+                    return true;
+                }
+                ops.pop();
+            }
+        }
+        false
+    }
+
+    /// Whether `remaining` can extend `state` to `target`, pushing the
+    /// operators used onto `ops`.
+    fn forward(&self, remaining: &[i64], target: i64, state: Partial, monotonic: bool,
+               ops: &mut Vec<Op>) -> bool {
+        let Some(value) = state.value() else { return false };
+        let Some((&next, rest)) = remaining.split_first() else { return value == target };
+        if monotonic && value > target {
+            return false;
+        }
+        for &op in &self.ops {
+            if let Some(next_state) = state.push(op, next, self.evaluation) {
+                ops.push(op);
+                if self.forward(rest, target, next_state, monotonic, ops) {
+                    return true;
+                }
+                ops.pop();
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_left_to_right() {
+        let cheap = ExpressionSolver::new(&[Op::Add, Op::Mul], Evaluation::LeftToRight);
+        let all = ExpressionSolver::new(&Op::ALL, Evaluation::LeftToRight);
+        // Equations from the 2024 day 7 example.
+        let witness = cheap.solve(&[81, 40, 27], 3267).unwrap();
+        assert_eq!(witness.evaluate(Evaluation::LeftToRight), Some(3267));
+        assert_eq!(cheap.solve(&[10, 19], 190).unwrap().to_string(), "10 * 19");
+        assert!(!cheap.is_solvable(&[6, 8, 6, 15], 7290));
+        let witness = all.solve(&[6, 8, 6, 15], 7290).unwrap();
+        assert_eq!(witness.to_string(), "6 * 8 || 6 * 15");
+        assert!(all.is_solvable(&[15, 6], 156));
+        assert!(!all.is_solvable(&[16, 10, 13], 161011));
+        assert!(all.is_solvable(&[7], 7) && !all.is_solvable(&[], 0));
+        // Overflowing expressions don't count.
+        assert!(!all.is_solvable(&[i64::MAX, 2], 0));
+        // Operands near the top of the type, searched both ways, find no
+        // solution rather than overflowing.
+        let big = 1_000_000_000_000_000_000;
+        assert!(!all.is_solvable(&[7, big], 1));
+        assert!(!all.is_solvable(&[7, big], i64::MIN));
+        assert!(!all.is_solvable(&[1, i64::MAX - 1], i64::MIN + 1));
+        assert!(!all.is_solvable(&[1, 0, big], 5));
+        let precedence = ExpressionSolver::new(&Op::ALL, Evaluation::Precedence);
+        assert!(!precedence.is_solvable(&[7, big, i64::MAX], 3));
+    }
+
+    /// Whether any choice of operators from `Op::ALL` reaches `target`.
+    fn brute_force(operands: &[i64], target: i64, evaluation: Evaluation) -> bool {
+        let n = operands.len() - 1;
+        (0..3usize.pow(n as u32)).any(|mut code| {
+            let ops = (0..n).map(|_| { let op = Op::ALL[code % 3]; code /= 3; op }).collect();
+            Expression { operands: operands.to_vec(), ops }.evaluate(evaluation) == Some(target)
+        })
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        // With a zero operand the search has to go forwards without pruning.
+        for evaluation in [Evaluation::LeftToRight, Evaluation::Precedence] {
+            let solver = ExpressionSolver::new(&Op::ALL, evaluation);
+            for operands in [vec![3, 4, 5, 2], vec![3, 0, 4, 5]] {
+                for target in 0..1000 {
+                    let witness = solver.solve(&operands, target);
+                    assert_eq!(witness.is_some(), brute_force(&operands, target, evaluation));
+                    if let Some(expression) = witness {
+                        assert_eq!(expression.evaluate(evaluation), Some(target));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_precedence() {
+        let solver = ExpressionSolver::new(&[Op::Add, Op::Mul], Evaluation::Precedence);
+        let witness = solver.solve(&[2, 3, 4], 14).unwrap();
+        assert_eq!(witness.to_string(), "2 + 3 * 4");
+        assert!(!solver.is_solvable(&[2, 3, 4], 20));
+        let expression = Expression { operands: vec![2, 3, 4, 5], ops: vec![Op::Mul, Op::Concat, Op::Add] };
+        assert_eq!(expression.evaluate(Evaluation::Precedence), Some(73));
+        assert_eq!(expression.evaluate(Evaluation::LeftToRight), Some(69));
+        assert!(ExpressionSolver::new(&Op::ALL, Evaluation::Precedence).is_solvable(&[2, 3, 4, 5], 73));
+    }
+}
@@ -0,0 +1,293 @@
+#![allow(dead_code)]
+//! Breadth- and depth-first search over implicit graphs.
+//!
+//! A graph is given by a `successors` closure from a node to the nodes one
+//! step away, so nothing needs to build an adjacency table first.  Every
+//! search may start from several nodes at once and records, for each node it
+//! reaches, its distance in steps and the node it was reached from, so that
+//! paths can be reconstructed afterwards.  For weighted graphs see
+//! `astar::Pathfinder`.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use super::grid_board::{Board, Direction, Xy};
+
+/// The nodes reached by a search, with how each was reached.
+#[derive(Debug, Clone)]
+pub struct SearchTree<NodeType> {
+    /// Nodes in the order they were first reached.
+    order: Vec<NodeType>,
+    /// Steps from the nearest start along the tree.  For breadth-first
+    /// searches this is the shortest distance.
+    distances: HashMap<NodeType, usize>,
+    /// The node each non-start node was first reached from.
+    parents: HashMap<NodeType, NodeType>,
+    /// The goal that stopped the search early, if any.
+    goal: Option<NodeType>,
+}
+
+impl<NodeType> SearchTree<NodeType>
+where NodeType: Eq + Hash + Clone,
+{
+    fn new() -> Self {
+        SearchTree { order: Vec::new(), distances: HashMap::new(), parents: HashMap::new(), goal: None }
+    }
+
+    /// Record `node` as reached from `parent`, returning false if it had
+    /// already been reached.
+    fn reach(&mut self, node: &NodeType, parent: Option<&NodeType>) -> bool {
+        if self.distances.contains_key(node) {
+            return false;
+        }
+        let distance = parent.map_or(0, |p| self.distances[p] + 1);
+        self.distances.insert(node.clone(), distance);
+        if let Some(p) = parent {
+            self.parents.insert(node.clone(), p.clone());
+        }
+        self.order.push(node.clone());
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    pub fn contains(&self, node: &NodeType) -> bool {
+        self.distances.contains_key(node)
+    }
+
+    /// Nodes in the order the search first reached them.
+    pub fn order(&self) -> &[NodeType] {
+        &self.order
+    }
+
+    pub fn reachable(&self) -> HashSet<NodeType> {
+        self.order.iter().cloned().collect()
+    }
+
+    pub fn distance(&self, node: &NodeType) -> Option<usize> {
+        self.distances.get(node).copied()
+    }
+
+    pub fn distances(&self) -> &HashMap<NodeType, usize> {
+        &self.distances
+    }
+
+    pub fn parent(&self, node: &NodeType) -> Option<&NodeType> {
+        self.parents.get(node)
+    }
+
+    pub fn parents(&self) -> &HashMap<NodeType, NodeType> {
+        &self.parents
+    }
+
+    /// The goal that ended the search, for searches with a goal.
+    pub fn goal(&self) -> Option<&NodeType> {
+        self.goal.as_ref()
+    }
+
+    /// The path through the search tree from a start to `node`, inclusive.
+    pub fn path_to(&self, node: &NodeType) -> Option<Vec<NodeType>> {
+        if !self.contains(node) {
+            return None;
+        }
+        let mut path = vec![node.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// The path to the goal that ended the search, if one was found.
+    pub fn goal_path(&self) -> Option<Vec<NodeType>> {
+        self.goal.as_ref().and_then(|goal| self.path_to(goal))
+    }
+}
+
+/// Breadth-first search from every node in `starts`, stopping as soon as a
+/// node satisfying `is_goal` is reached.
+pub fn bfs_until<NodeType, Successors>(
+    starts: impl IntoIterator<Item = NodeType>,
+    successors: impl Fn(&NodeType) -> Successors,
+    is_goal: impl Fn(&NodeType) -> bool,
+) -> SearchTree<NodeType>
+where NodeType: Eq + Hash + Clone,
+      Successors: IntoIterator<Item = NodeType>,
+{
+    let mut tree = SearchTree::new();
+    let mut queue: VecDeque<NodeType> = VecDeque::new();
+    for start in starts {
+        if tree.reach(&start, None) {
+            if is_goal(&start) {
+                tree.goal = Some(start);
+                return tree;
+            }
+            queue.push_back(start);
+        }
+    }
+    while let Some(node) = queue.pop_front() {
+        for next in successors(&node) {
+            if tree.reach(&next, Some(&node)) {
+                if is_goal(&next) {
+                    tree.goal = Some(next);
+                    return tree;
+                }
+                queue.push_back(next);
+            }
+        }
+    }
+    tree
+}
+
+/// Breadth-first search of everything reachable from `starts`.
+pub fn bfs<NodeType, Successors>(
+    starts: impl IntoIterator<Item = NodeType>,
+    successors: impl Fn(&NodeType) -> Successors,
+) -> SearchTree<NodeType>
+where NodeType: Eq + Hash + Clone,
+      Successors: IntoIterator<Item = NodeType>,
+{
+    bfs_until(starts, successors, |_| false)
+}
+
+/// Depth-first search from every node in `starts`, stopping as soon as a
+/// node satisfying `is_goal` is reached.  Distances are depths in the search
+/// tree, not shortest distances.
+pub fn dfs_until<NodeType, Successors>(
+    starts: impl IntoIterator<Item = NodeType>,
+    successors: impl Fn(&NodeType) -> Successors,
+    is_goal: impl Fn(&NodeType) -> bool,
+) -> SearchTree<NodeType>
+where NodeType: Eq + Hash + Clone,
+      Successors: IntoIterator<Item = NodeType>,
+{
+    let mut tree = SearchTree::new();
+    // Each entry is a node and the node it was pushed from; a node is only
+    // reached when popped, so the tree follows the depth-first order.
+    let mut stack: Vec<(NodeType, Option<NodeType>)> = Vec::new();
+    let starts: Vec<NodeType> = starts.into_iter().collect();
+    stack.extend(starts.into_iter().rev().map(|start| (start, None)));
+    while let Some((node, parent)) = stack.pop() {
+        if !tree.reach(&node, parent.as_ref()) {
+            continue;
+        }
+        if is_goal(&node) {
+            tree.goal = Some(node);
+            return tree;
+        }
+        let nexts: Vec<NodeType> = successors(&node)
+            .into_iter()
+            .filter(|next| !tree.contains(next))
+            .collect();
+        stack.extend(nexts.into_iter().rev().map(|next| (next, Some(node.clone()))));
+    }
+    tree
+}
+
+/// Depth-first search of everything reachable from `starts`.
+pub fn dfs<NodeType, Successors>(
+    starts: impl IntoIterator<Item = NodeType>,
+    successors: impl Fn(&NodeType) -> Successors,
+) -> SearchTree<NodeType>
+where NodeType: Eq + Hash + Clone,
+      Successors: IntoIterator<Item = NodeType>,
+{
+    dfs_until(starts, successors, |_| false)
+}
+
+/// Every node reachable from `starts`, including the starts themselves.
+pub fn reachable<NodeType, Successors>(
+    starts: impl IntoIterator<Item = NodeType>,
+    successors: impl Fn(&NodeType) -> Successors,
+) -> HashSet<NodeType>
+where NodeType: Eq + Hash + Clone,
+      Successors: IntoIterator<Item = NodeType>,
+{
+    bfs(starts, successors).reachable()
+}
+
+impl<BoardContent> Board<BoardContent>
+where BoardContent: Copy + Debug + PartialEq,
+{
+    /// The orthogonal neighbors of `xy` that can be stepped to from it:
+    /// those on the board for which `passable(from_value, to_value)` holds.
+    pub fn passable_neighbors(&self, xy: Xy,
+                              passable: impl Fn(BoardContent, BoardContent) -> bool) -> Vec<Xy> {
+        let here = self.at(xy);
+        Direction::ALL
+            .iter()
+            .map(|d| xy + d.to_offset())
+            .filter(|&next| self.maybe_at(next).is_some_and(|there| passable(here, there)))
+            .collect()
+    }
+
+    /// Breadth-first search over orthogonal steps from `starts`, taking a step
+    /// only where `passable(from_value, to_value)` holds.
+    pub fn bfs(&self, starts: impl IntoIterator<Item = Xy>,
+               passable: impl Fn(BoardContent, BoardContent) -> bool) -> SearchTree<Xy> {
+        bfs(starts, |&xy| self.passable_neighbors(xy, &passable))
+    }
+
+    /// Every cell reachable from `starts` by orthogonal steps where
+    /// `passable(from_value, to_value)` holds.
+    pub fn reachable(&self, starts: impl IntoIterator<Item = Xy>,
+                     passable: impl Fn(BoardContent, BoardContent) -> bool) -> HashSet<Xy> {
+        self.bfs(starts, passable).reachable()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn maze() -> Board<char> {
+        Board::from_strings(&["S.#.", ".##.", "...E"])
+    }
+
+    #[test]
+    fn test_bfs_on_board() {
+        let board = maze();
+        let open = |_, to| to != '#';
+        let tree = board.bfs([Xy::new(0, 0)], open);
+        assert_eq!(tree.distance(&Xy::new(3, 2)), Some(5));
+        assert_eq!(tree.distance(&Xy::new(3, 0)), Some(7));
+        assert_eq!(tree.len(), 9);
+        let path = tree.path_to(&Xy::new(3, 2)).unwrap();
+        assert_eq!(path.len(), 6);
+        assert_eq!(path[0], Xy::new(0, 0));
+        assert!(!tree.contains(&Xy::new(2, 0)));
+
+        // Two sources: every cell is now within 3 steps of one of them.
+        let tree = board.bfs([Xy::new(0, 0), Xy::new(3, 0)], open);
+        assert_eq!(tree.distances().values().max(), Some(&3));
+        assert_eq!(tree.parent(&Xy::new(3, 1)), Some(&Xy::new(3, 0)));
+    }
+
+    #[test]
+    fn test_early_exit() {
+        let successors = |&n: &u32| if n < 1000 { vec![n * 2, n + 1] } else { vec![] };
+        let tree = bfs_until([1], successors, |&n| n == 10);
+        assert_eq!(tree.goal(), Some(&10));
+        // 1 -> 2 -> 4 -> 5 -> 10
+        assert_eq!(tree.goal_path(), Some(vec![1, 2, 4, 5, 10]));
+        assert!(tree.len() < 20);
+        assert_eq!(bfs_until([1], successors, |&n| n == 0).goal(), None);
+    }
+
+    #[test]
+    fn test_dfs() {
+        let successors = |&n: &u32| if n < 4 { vec![2 * n, 2 * n + 1] } else { vec![] };
+        let tree = dfs([1], successors);
+        assert_eq!(tree.order(), &[1, 2, 4, 5, 3, 6, 7]);
+        assert_eq!(tree.distance(&7), Some(2));
+        assert_eq!(dfs_until([1], successors, |&n| n == 5).path_to(&5), Some(vec![1, 2, 5]));
+        assert_eq!(reachable([4, 2], successors), HashSet::from([2, 4, 5]));
+    }
+}
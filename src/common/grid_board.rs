@@ -3,6 +3,8 @@
 use std::fmt::Debug;
 use nalgebra::Vector2;
 
+use super::pattern_search::PatternSearcher;
+
 pub type Scalar = i64;
 pub type Xy = Vector2<Scalar>;
 
@@ -16,6 +18,39 @@ pub fn xy_range(ul: Xy, br: Xy) -> Vec<Xy> {
     result
 }
 
+/// The unbounded ray `start, start + step, start + 2 * step, ...`.
+pub fn ray(start: Xy, step: Xy) -> impl Iterator<Item = Xy> {
+    assert_ne!(step, Xy::zeros(), "A ray needs a nonzero step");
+    std::iter::successors(Some(start), move |&xy| Some(xy + step))
+}
+
+/// The cells of a line from `from` to `to`, both included, by Bresenham's
+/// algorithm.  Horizontal, vertical and diagonal lines are exact; other
+/// slopes pick the cell nearest the true line in each column or row.
+pub fn rasterize_line(from: Xy, to: Xy) -> Vec<Xy> {
+    let delta = to - from;
+    let (dx, dy) = (delta[0].abs(), -delta[1].abs());
+    let step = Xy::new(delta[0].signum(), delta[1].signum());
+    let mut result = Vec::with_capacity((dx.max(-dy) + 1) as usize);
+    let mut xy = from;
+    let mut error = dx + dy;
+    loop {
+        result.push(xy);
+        if xy == to {
+            return result;
+        }
+        let twice_error = 2 * error;
+        if twice_error >= dy {
+            error += dy;
+            xy[0] += step[0];
+        }
+        if twice_error <= dx {
+            error += dx;
+            xy[1] += step[1];
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Direction {
     North,
@@ -204,13 +239,15 @@ where BoardContent: Copy + Debug + PartialEq,
         result
     }
 
-    pub fn from_strings(strings: &Vec<String>) -> Board<BoardContent>
+    /// One row per string, e.g. `Board::from_strings(&["#.", ".#"])`.
+    pub fn from_strings(strings: &[impl AsRef<str>]) -> Board<BoardContent>
     where BoardContent: TryFrom<char, Error: Debug>,
     {
         let mut result = Board { board: Vec::new() };
         for row_string in strings {
             result.board.push(
                 row_string
+                    .as_ref()
                     .chars()
                     .map(|c| BoardContent::try_from(c).unwrap())
                     .collect(),
@@ -292,31 +329,17 @@ where BoardContent: Copy + Debug + PartialEq,
     }
 
     /// Scan the board for occurrences of the given "needle" board.
-    /// The needle may contain `None` values, which are treated as wildcards
+    /// The needle may contain `None` values, which are treated as wildcards.
+    /// To search for several needles, or for rotations and reflections of a
+    /// needle, use a `PatternSearcher` directly.
     pub fn scan(&self, needle: &Board<Option<BoardContent>>) -> Board<bool>
     where BoardContent: Eq,
     {
-        let new_w = self.width() - needle.width() + 1;
-        let new_h = self.height() - needle.height() + 1;
-        let mut result = Board {
-            board: vec![vec![true; new_w]; new_h],
-        };
-        for result_xy in result.all_coords() {
-            let [result_x, result_y] = result_xy.into();
-            for needle_xy in needle.all_coords() {
-                let [needle_x, needle_y] = needle_xy.into();
-                let haystack_xy = Xy::new(result_x + needle_x, result_y + needle_y);
-                let needle_cell = needle.at(needle_xy);
-                if let Some(needle_cell_value) = needle_cell {
-                    let haystack_cell = self.at(haystack_xy);
-                    if haystack_cell != needle_cell_value {
-                        result.set_at(result_xy, false);
-                        break;
-                    }
-                }
-            }
-        }
-        result
+        assert!(needle.width() <= self.width() && needle.height() <= self.height());
+        PatternSearcher::new(std::slice::from_ref(needle))
+            .scan_boards(self)
+            .pop()
+            .unwrap()
     }
 
     /// Replace multiple locations on the board according to the given
@@ -369,18 +392,171 @@ where BoardContent: Copy + Debug + PartialEq,
             current_y: 0,
         }
     }
-}
 
-impl std::fmt::Display for Board<bool> {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let mut result = String::new();
-        for row in &self.board {
-            for &cell in row {
-                result.push(if cell { '#' } else { '.' });
-            }
-            result.push('\n');
+    /// A borrowed view of the `dimensions`-sized rectangle of this board whose
+    /// upper left corner is at `ul`.
+    pub fn view(&self, ul: Xy, dimensions: Xy) -> BoardView<'_, BoardContent> {
+        assert!(ul[0] >= 0 && ul[1] >= 0 && dimensions[0] >= 0 && dimensions[1] >= 0);
+        assert!(ul[0] + dimensions[0] <= self.width() as Scalar
+                && ul[1] + dimensions[1] <= self.height() as Scalar,
+                "View at {:?} of size {:?} exceeds board of size {:?}",
+                ul, dimensions, self.dimensions());
+        BoardView { board: self, origin: ul, dimensions }
+    }
+
+    /// A view of the entire board.
+    pub fn as_view(&self) -> BoardView<'_, BoardContent> {
+        self.view(Xy::new(0, 0), self.dimensions())
+    }
+
+    /// Iterate over every placement of a `w` x `h` window on this board, in
+    /// row-major order of the window's upper left corner.
+    pub fn windows(&self, w: usize, h: usize) -> BoardWindows<'_, BoardContent> {
+        BoardWindows {
+            board: self,
+            dimensions: Xy::new(w as Scalar, h as Scalar),
+            next_ul: Some(Xy::new(0, 0)).filter(|_| w <= self.width() && h <= self.height()),
+        }
+    }
+
+    /// Copy the contents of `source` onto this board with the view's upper
+    /// left corner landing at `offset`.  Cells that would land outside of
+    /// this board are discarded.
+    pub fn blit(&mut self, source: &BoardView<BoardContent>, offset: Xy) {
+        for (xy, c) in source.iter() {
+            self.maybe_set_at(xy + offset, c);
         }
-        write!(f, "{}", result)
+    }
+
+    /// The cells along the ray from `start` by `step`, starting with `start`
+    /// itself, until the ray leaves the board.
+    pub fn walk_ray(&self, start: Xy, step: Xy) -> impl Iterator<Item = (Xy, BoardContent)> + '_ {
+        ray(start, step).map_while(move |xy| self.maybe_at(xy).map(|c| (xy, c)))
+    }
+
+    /// The cells a walker passes through moving from `start` by `step`,
+    /// excluding `start`, stopping before the first cell that is `blocked`
+    /// or at the edge of the board.
+    pub fn walk_until_blocked(&self, start: Xy, step: Xy,
+                              blocked: impl Fn(BoardContent) -> bool) -> Vec<Xy> {
+        self.walk_ray(start + step, step)
+            .take_while(|&(_, c)| !blocked(c))
+            .map(|(xy, _)| xy)
+            .collect()
+    }
+
+    /// The first cell after `start` along the ray by `step` that satisfies
+    /// `pred`, or `None` if the ray leaves the board first.
+    pub fn first_hit(&self, start: Xy, step: Xy,
+                     pred: impl Fn(BoardContent) -> bool) -> Option<(Xy, BoardContent)> {
+        self.walk_ray(start + step, step).find(|&(_, c)| pred(c))
+    }
+}
+
+/// A borrowed rectangular region of a `Board`.  Coordinates passed to a view
+/// are relative to the view's own upper left corner.
+#[derive(Clone, Copy)]
+pub struct BoardView<'a, BoardContent> {
+    board: &'a Board<BoardContent>,
+    origin: Xy,
+    dimensions: Xy,
+}
+
+impl<'a, BoardContent> BoardView<'a, BoardContent>
+where BoardContent: Copy + Debug + PartialEq,
+{
+    pub fn width(&self) -> usize {
+        self.dimensions[0] as usize
+    }
+    pub fn height(&self) -> usize {
+        self.dimensions[1] as usize
+    }
+    pub fn dimensions(&self) -> Xy {
+        self.dimensions
+    }
+
+    /// The location of this view's upper left corner on the underlying board.
+    pub fn origin(&self) -> Xy {
+        self.origin
+    }
+
+    pub fn at(&self, xy: Xy) -> BoardContent {
+        debug_assert!(self.contains(xy));
+        self.board.at(self.origin + xy)
+    }
+
+    pub fn maybe_at(&self, xy: Xy) -> Option<BoardContent> {
+        if self.contains(xy) { Some(self.at(xy)) } else { None }
+    }
+
+    pub fn contains(&self, xy: Xy) -> bool {
+        xy[0] >= 0 && xy[0] < self.dimensions[0] && xy[1] >= 0 && xy[1] < self.dimensions[1]
+    }
+
+    /// The `y`th row of the view, borrowed from the underlying board.
+    pub fn row(&self, y: usize) -> &'a [BoardContent] {
+        let x0 = self.origin[0] as usize;
+        &self.board.board[self.origin[1] as usize + y][x0..x0 + self.width()]
+    }
+
+    /// Iterate over (view-relative coordinates, content) in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (Xy, BoardContent)> + 'a {
+        let view = *self;
+        (0..self.height()).flat_map(move |y| {
+            view.row(y)
+                .iter()
+                .enumerate()
+                .map(move |(x, &c)| (Xy::new(x as Scalar, y as Scalar), c))
+        })
+    }
+
+    /// A sub-view of this view; `ul` is relative to this view.
+    pub fn view(&self, ul: Xy, dimensions: Xy) -> BoardView<'a, BoardContent> {
+        assert!(ul[0] >= 0 && ul[1] >= 0
+                && ul[0] + dimensions[0] <= self.dimensions[0]
+                && ul[1] + dimensions[1] <= self.dimensions[1]);
+        BoardView { board: self.board, origin: self.origin + ul, dimensions }
+    }
+
+    /// Copy the contents of this view into a new, owned board.
+    pub fn to_board(self) -> Board<BoardContent> {
+        Board { board: (0..self.height()).map(|y| self.row(y).to_vec()).collect() }
+    }
+}
+
+impl<BoardContent> PartialEq for BoardView<'_, BoardContent>
+where BoardContent: Copy + Debug + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.dimensions == other.dimensions
+            && (0..self.height()).all(|y| self.row(y) == other.row(y))
+    }
+}
+
+/// Iterator over all placements of a fixed-size window on a board; see
+/// `Board::windows`.
+pub struct BoardWindows<'a, BoardContent> {
+    board: &'a Board<BoardContent>,
+    dimensions: Xy,
+    next_ul: Option<Xy>,
+}
+
+impl<'a, BoardContent> Iterator for BoardWindows<'a, BoardContent>
+where BoardContent: Copy + Debug + PartialEq,
+{
+    type Item = BoardView<'a, BoardContent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ul = self.next_ul?;
+        let last = self.board.dimensions() - self.dimensions;
+        self.next_ul = if ul[0] < last[0] {
+            Some(ul + Xy::new(1, 0))
+        } else if ul[1] < last[1] {
+            Some(Xy::new(0, ul[1] + 1))
+        } else {
+            None
+        };
+        Some(self.board.view(ul, self.dimensions))
     }
 }
 
@@ -391,8 +567,7 @@ pub struct BoardIterator<'a, BoardContent> {
 }
 impl<BoardContent> Iterator for BoardIterator<'_, BoardContent>
 where
-    BoardContent: Copy + Debug + ToString + PartialEq,
-    BoardContent: TryFrom<char, Error: Debug>,
+    BoardContent: Copy + Debug + PartialEq,
 {
     type Item = (Xy, BoardContent);
 
@@ -411,3 +586,68 @@ where
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn abc_board() -> Board<char> {
+        Board::from_strings(&["abc", "def", "ghi"])
+    }
+
+    #[test]
+    fn test_view() {
+        let board = abc_board();
+        let view = board.view(Xy::new(1, 1), Xy::new(2, 2));
+        assert_eq!(view.at(Xy::new(0, 0)), 'e');
+        assert_eq!(view.at(Xy::new(1, 1)), 'i');
+        assert_eq!(view.maybe_at(Xy::new(2, 0)), None);
+        assert_eq!(view.row(1), &['h', 'i']);
+        let contents: String = view.iter().map(|(_, c)| c).collect();
+        assert_eq!(contents, "efhi");
+        assert_eq!(view.view(Xy::new(1, 0), Xy::new(1, 2)).to_board().to_strings(),
+                   vec!["f", "i"]);
+    }
+
+    #[test]
+    fn test_windows() {
+        let board = abc_board();
+        let origins: Vec<Xy> = board.windows(2, 2).map(|w| w.origin()).collect();
+        assert_eq!(origins, vec![Xy::new(0, 0), Xy::new(1, 0), Xy::new(0, 1), Xy::new(1, 1)]);
+        assert_eq!(board.windows(3, 1).count(), 3);
+        assert_eq!(board.windows(4, 1).count(), 0);
+    }
+
+    #[test]
+    fn test_blit() {
+        let source = abc_board();
+        let mut target = Board::new(3, 4, '.');
+        target.blit(&source.view(Xy::new(0, 0), Xy::new(2, 2)), Xy::new(2, 2));
+        assert_eq!(target.to_strings(), vec!["....", "....", "..ab"]);
+    }
+
+    #[test]
+    fn test_rays() {
+        let board = abc_board();
+        let diagonal: String = board.walk_ray(Xy::new(0, 0), Xy::new(1, 1)).map(|(_, c)| c).collect();
+        assert_eq!(diagonal, "aei");
+        assert_eq!(board.walk_ray(Xy::new(3, 0), Xy::new(1, 0)).count(), 0);
+        assert_eq!(board.walk_until_blocked(Xy::new(0, 0), Xy::new(1, 0), |c| c == 'c'),
+                   vec![Xy::new(1, 0)]);
+        assert_eq!(board.first_hit(Xy::new(2, 2), Xy::new(0, -1), |c| c < 'd'),
+                   Some((Xy::new(2, 0), 'c')));
+        assert_eq!(board.first_hit(Xy::new(0, 0), Xy::new(0, -1), |_| true), None);
+    }
+
+    #[test]
+    fn test_rasterize_line() {
+        assert_eq!(rasterize_line(Xy::new(2, 2), Xy::new(2, 2)), vec![Xy::new(2, 2)]);
+        assert_eq!(rasterize_line(Xy::new(3, 1), Xy::new(0, -2)),
+                   vec![Xy::new(3, 1), Xy::new(2, 0), Xy::new(1, -1), Xy::new(0, -2)]);
+        assert_eq!(rasterize_line(Xy::new(0, 0), Xy::new(4, 2)),
+                   vec![Xy::new(0, 0), Xy::new(1, 1), Xy::new(2, 1), Xy::new(3, 2), Xy::new(4, 2)]);
+        let steep = rasterize_line(Xy::new(0, 5), Xy::new(-2, 0));
+        assert_eq!(steep.len(), 6);
+        assert!(steep.windows(2).all(|pair| (pair[1] - pair[0]).abs().max() == 1));
+    }
+}
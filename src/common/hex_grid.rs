@@ -0,0 +1,421 @@
+#![allow(dead_code)]
+//! Hexagonal grids.
+//!
+//! Cells are addressed with axial coordinates `(q, r)`; the implied third
+//! cube coordinate is `s = -q - r`.  The six neighbor offsets are the same
+//! whichever way the hexagons are drawn, but puzzles name them differently:
+//! flat-topped grids step `n`/`ne`/`se`/`s`/`sw`/`nw`, while pointy-topped
+//! grids step `e`/`se`/`sw`/`w`/`nw`/`ne`.  Both namings are provided.
+
+use std::fmt::{Debug, Display};
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+use std::str::FromStr;
+
+use super::grid_board::Scalar;
+use super::tile::Tile;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Hex {
+    pub q: Scalar,
+    pub r: Scalar,
+}
+
+/// The six axial unit offsets, counterclockwise starting from `+q`.
+const HEX_OFFSETS: [Hex; 6] = [
+    Hex { q: 1, r: 0 },
+    Hex { q: 1, r: -1 },
+    Hex { q: 0, r: -1 },
+    Hex { q: -1, r: 0 },
+    Hex { q: -1, r: 1 },
+    Hex { q: 0, r: 1 },
+];
+
+impl Hex {
+    pub const ORIGIN: Hex = Hex { q: 0, r: 0 };
+
+    pub fn new(q: Scalar, r: Scalar) -> Hex {
+        Hex { q, r }
+    }
+
+    pub fn from_cube(q: Scalar, r: Scalar, s: Scalar) -> Hex {
+        assert_eq!(q + r + s, 0, "Cube coordinates must sum to zero");
+        Hex { q, r }
+    }
+
+    pub fn s(self) -> Scalar {
+        -self.q - self.r
+    }
+
+    pub fn cube(self) -> (Scalar, Scalar, Scalar) {
+        (self.q, self.r, self.s())
+    }
+
+    /// The number of steps from the origin.
+    pub fn length(self) -> Scalar {
+        (self.q.abs() + self.r.abs() + self.s().abs()) / 2
+    }
+
+    /// The number of steps between two cells.
+    pub fn distance(self, other: Hex) -> Scalar {
+        (self - other).length()
+    }
+
+    pub fn neighbors(self) -> [Hex; 6] {
+        HEX_OFFSETS.map(|offset| self + offset)
+    }
+
+    /// The cells exactly `radius` steps from this one, going counterclockwise.
+    pub fn ring(self, radius: Scalar) -> Vec<Hex> {
+        if radius == 0 {
+            return vec![self];
+        }
+        let mut result = Vec::with_capacity(6 * radius as usize);
+        // Start at the corner reached by `radius` steps along the last offset,
+        // then walk each side in turn.
+        let mut cell = self + HEX_OFFSETS[4] * radius;
+        for side in HEX_OFFSETS {
+            for _ in 0..radius {
+                result.push(cell);
+                cell += side;
+            }
+        }
+        result
+    }
+
+    /// The cells at most `radius` steps from this one, ring by ring.
+    pub fn spiral(self, radius: Scalar) -> Vec<Hex> {
+        (0..=radius).flat_map(|k| self.ring(k)).collect()
+    }
+
+    /// Rotate a sixth of a turn counterclockwise about the origin.
+    pub fn rotate_ccw(self) -> Hex {
+        let (q, r, s) = self.cube();
+        Hex::from_cube(-s, -q, -r)
+    }
+
+    /// Rotate a sixth of a turn clockwise about the origin.
+    pub fn rotate_cw(self) -> Hex {
+        let (q, r, s) = self.cube();
+        Hex::from_cube(-r, -s, -q)
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+    fn add(self, other: Hex) -> Hex {
+        Hex::new(self.q + other.q, self.r + other.r)
+    }
+}
+
+impl AddAssign for Hex {
+    fn add_assign(&mut self, other: Hex) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+    fn sub(self, other: Hex) -> Hex {
+        Hex::new(self.q - other.q, self.r - other.r)
+    }
+}
+
+impl Neg for Hex {
+    type Output = Hex;
+    fn neg(self) -> Hex {
+        Hex::new(-self.q, -self.r)
+    }
+}
+
+impl Mul<Scalar> for Hex {
+    type Output = Hex;
+    fn mul(self, k: Scalar) -> Hex {
+        Hex::new(self.q * k, self.r * k)
+    }
+}
+
+macro_rules! hex_directions {
+    ($name:ident, $doc:literal, [$($variant:ident = $text:literal => ($q:literal, $r:literal)),* $(,)?]) => {
+        #[doc = $doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub enum $name {
+            $($variant,)*
+        }
+
+        impl $name {
+            /// All six directions, clockwise.
+            pub const ALL: [$name; 6] = [$($name::$variant,)*];
+
+            pub fn to_offset(self) -> Hex {
+                match self {
+                    $($name::$variant => Hex::new($q, $r),)*
+                }
+            }
+
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $($name::$variant => $text,)*
+                }
+            }
+
+            fn index(self) -> usize {
+                Self::ALL.iter().position(|&d| d == self).unwrap()
+            }
+
+            pub fn cw(self) -> $name {
+                Self::ALL[(self.index() + 1) % 6]
+            }
+
+            pub fn ccw(self) -> $name {
+                Self::ALL[(self.index() + 5) % 6]
+            }
+
+            pub fn opposite(self) -> $name {
+                Self::ALL[(self.index() + 3) % 6]
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s.trim().to_ascii_lowercase().as_str() {
+                    $($text => Ok($name::$variant),)*
+                    _ => Err(format!("Unknown {} `{}`", stringify!($name), s)),
+                }
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self.as_str())
+            }
+        }
+    };
+}
+
+hex_directions!(FlatDirection, "Directions on a grid of flat-topped hexagons.", [
+    North = "n" => (0, -1),
+    NorthEast = "ne" => (1, -1),
+    SouthEast = "se" => (1, 0),
+    South = "s" => (0, 1),
+    SouthWest = "sw" => (-1, 1),
+    NorthWest = "nw" => (-1, 0),
+]);
+
+hex_directions!(PointyDirection, "Directions on a grid of pointy-topped hexagons.", [
+    East = "e" => (1, 0),
+    SouthEast = "se" => (0, 1),
+    SouthWest = "sw" => (-1, 1),
+    West = "w" => (-1, 0),
+    NorthWest = "nw" => (0, -1),
+    NorthEast = "ne" => (1, -1),
+]);
+
+/// Parse a separated list of flat-topped directions such as `"ne,ne,s"`.
+/// Commas and whitespace are both accepted as separators.
+pub fn parse_flat_path(text: &str) -> Result<Vec<FlatDirection>, String> {
+    text.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// Parse an unseparated run of pointy-topped directions such as `"esenee"`.
+pub fn parse_pointy_path(text: &str) -> Result<Vec<PointyDirection>, String> {
+    let mut result = Vec::new();
+    let mut chars = text.trim().chars();
+    while let Some(c) = chars.next() {
+        let step = match c {
+            'n' | 's' => format!("{}{}", c, chars.next().unwrap_or(' ')),
+            _ => c.to_string(),
+        };
+        result.push(step.parse()?);
+    }
+    Ok(result)
+}
+
+/// Where a walk from `start` along `offsets` ends up.
+pub fn walk(start: Hex, offsets: impl IntoIterator<Item = Hex>) -> Hex {
+    offsets.into_iter().fold(start, |cell, offset| cell + offset)
+}
+
+/// A hexagon-shaped board of cells within `radius` steps of the origin.
+#[derive(Clone, PartialEq)]
+pub struct HexBoard<BoardContent> {
+    radius: Scalar,
+    /// Row-major over the (2 * radius + 1)^2 parallelogram; cells outside the
+    /// hexagon are present but never used.
+    cells: Vec<BoardContent>,
+}
+
+impl<BoardContent> HexBoard<BoardContent>
+where BoardContent: Copy + Debug + PartialEq,
+{
+    pub fn new(radius: usize, default_value: BoardContent) -> Self {
+        let side = 2 * radius + 1;
+        HexBoard { radius: radius as Scalar, cells: vec![default_value; side * side] }
+    }
+
+    pub fn radius(&self) -> usize {
+        self.radius as usize
+    }
+
+    pub fn contains(&self, hex: Hex) -> bool {
+        hex.length() <= self.radius
+    }
+
+    fn index(&self, hex: Hex) -> usize {
+        assert!(self.contains(hex), "{:?} is off the board", hex);
+        let side = 2 * self.radius + 1;
+        ((hex.r + self.radius) * side + hex.q + self.radius) as usize
+    }
+
+    /// Every cell of the board, row by row from the top.
+    pub fn all_coords(&self) -> Vec<Hex> {
+        let mut result = Vec::new();
+        for r in -self.radius..=self.radius {
+            for q in (-self.radius).max(-self.radius - r)..=self.radius.min(self.radius - r) {
+                result.push(Hex::new(q, r));
+            }
+        }
+        result
+    }
+
+    pub fn coords_of(&self, content: BoardContent) -> Vec<Hex> {
+        self.all_coords().into_iter().filter(|&hex| self.at(hex) == content).collect()
+    }
+
+    pub fn at(&self, hex: Hex) -> BoardContent {
+        self.cells[self.index(hex)]
+    }
+
+    pub fn mut_at(&mut self, hex: Hex) -> &mut BoardContent {
+        let index = self.index(hex);
+        &mut self.cells[index]
+    }
+
+    pub fn maybe_at(&self, hex: Hex) -> Option<BoardContent> {
+        if self.contains(hex) { Some(self.at(hex)) } else { None }
+    }
+
+    pub fn set_at(&mut self, hex: Hex, c: BoardContent) {
+        let index = self.index(hex);
+        self.cells[index] = c;
+    }
+
+    pub fn maybe_set_at(&mut self, hex: Hex, c: BoardContent) {
+        if self.contains(hex) {
+            self.set_at(hex, c);
+        }
+    }
+
+    /// The contents of the in-bounds neighbors of `hex`.
+    pub fn neighbors(&self, hex: Hex) -> Vec<BoardContent> {
+        hex.neighbors().iter().filter_map(|&n| self.maybe_at(n)).collect()
+    }
+
+    pub fn count(&self, target: BoardContent) -> usize {
+        self.all_coords().into_iter().filter(|&hex| self.at(hex) == target).count()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Hex, BoardContent)> + '_ {
+        self.all_coords().into_iter().map(|hex| (hex, self.at(hex)))
+    }
+
+    /// Rows of text drawing the board as pointy-topped hexagons:  each row is
+    /// indented by half a cell per step from the middle row.
+    pub fn to_strings(&self) -> Vec<String>
+    where BoardContent: Tile,
+    {
+        let mut result = Vec::new();
+        for r in -self.radius..=self.radius {
+            let mut row = " ".repeat(r.unsigned_abs() as usize);
+            let q_min = (-self.radius).max(-self.radius - r);
+            let q_max = self.radius.min(self.radius - r);
+            let cells: Vec<String> = (q_min..=q_max)
+                .map(|q| self.at(Hex::new(q, r)).to_char().to_string())
+                .collect();
+            row.push_str(&cells.join(" "));
+            result.push(row);
+        }
+        result
+    }
+}
+
+impl<BoardContent> Display for HexBoard<BoardContent>
+where BoardContent: Tile,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.to_strings() {
+            writeln!(f, "{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flat_distance(path: &str) -> Scalar {
+        let steps = parse_flat_path(path).unwrap();
+        walk(Hex::ORIGIN, steps.iter().map(|d| d.to_offset())).length()
+    }
+
+    #[test]
+    fn test_flat_paths() {
+        assert_eq!(flat_distance("ne,ne,ne"), 3);
+        assert_eq!(flat_distance("ne,ne,sw,sw"), 0);
+        assert_eq!(flat_distance("ne,ne,s,s"), 2);
+        assert_eq!(flat_distance("se,sw,se,sw,sw"), 3);
+        assert!(parse_flat_path("ne,x").is_err());
+    }
+
+    #[test]
+    fn test_pointy_paths() {
+        let steps = parse_pointy_path("nwwswee").unwrap();
+        assert_eq!(steps.len(), 5);
+        assert_eq!(walk(Hex::ORIGIN, steps.iter().map(|d| d.to_offset())), Hex::ORIGIN);
+        let steps = parse_pointy_path("esew").unwrap();
+        assert_eq!(walk(Hex::ORIGIN, steps.iter().map(|d| d.to_offset())),
+                   PointyDirection::SouthEast.to_offset());
+    }
+
+    #[test]
+    fn test_geometry() {
+        let center = Hex::new(2, -1);
+        for radius in 0..4 {
+            let ring = center.ring(radius);
+            assert_eq!(ring.len(), if radius == 0 { 1 } else { 6 * radius as usize });
+            assert!(ring.iter().all(|&h| h.distance(center) == radius));
+        }
+        assert_eq!(center.spiral(2).len(), 19);
+        assert_eq!(PointyDirection::East.cw(), PointyDirection::SouthEast);
+        assert_eq!(FlatDirection::North.opposite(), FlatDirection::South);
+        let h = Hex::new(3, -1);
+        assert_eq!(h.rotate_cw().rotate_ccw(), h);
+        assert_eq!((0..6).fold(h, |h, _| h.rotate_cw()), h);
+        // Rotating an offset agrees with turning its direction.
+        for d in FlatDirection::ALL {
+            assert_eq!(d.to_offset().rotate_cw(), d.cw().to_offset(), "{}", d);
+            assert_eq!(d.to_offset().rotate_ccw(), d.ccw().to_offset(), "{}", d);
+        }
+        for d in PointyDirection::ALL {
+            assert_eq!(d.to_offset().rotate_cw(), d.cw().to_offset(), "{}", d);
+            assert_eq!(d.to_offset().rotate_ccw(), d.ccw().to_offset(), "{}", d);
+        }
+    }
+
+    #[test]
+    fn test_board() {
+        let mut board = HexBoard::new(1, '.');
+        assert_eq!(board.all_coords().len(), 7);
+        board.set_at(Hex::ORIGIN, '#');
+        board.set_at(Hex::new(1, -1), '#');
+        assert_eq!(board.maybe_at(Hex::new(1, 1)), None);
+        assert_eq!(board.neighbors(Hex::new(1, 0)).len(), 3);
+        assert_eq!(board.count('#'), 2);
+        assert_eq!(board.to_string(), " . #\n. # .\n . .\n");
+    }
+}
@@ -0,0 +1,243 @@
+#![allow(dead_code)]
+//! Sets of integers stored as sorted, disjoint, inclusive ranges.
+//!
+//! Ranges that overlap or merely touch (`1..=3` and `4..=6`) are merged as
+//! they are inserted, so the stored ranges are always the fewest that cover
+//! the set.  Membership is a binary search over them.
+
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+
+use num::PrimInt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    /// Sorted (start, end) pairs, inclusive, with a gap of at least one
+    /// value between consecutive pairs.
+    ranges: Vec<(T, T)>,
+}
+
+impl<T: PrimInt> Default for IntervalSet<T> {
+    fn default() -> Self {
+        IntervalSet { ranges: Vec::new() }
+    }
+}
+
+impl<T: PrimInt + Debug> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of disjoint ranges.
+    pub fn range_count(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The number of values in the set.
+    pub fn total_length(&self) -> T {
+        self.ranges.iter().fold(T::zero(), |total, &(start, end)| total + (end - start) + T::one())
+    }
+
+    /// The index of the first stored range that ends at or after `value`.
+    fn first_ending_at_or_after(&self, value: T) -> usize {
+        self.ranges.partition_point(|&(_, end)| end < value)
+    }
+
+    /// The index of the first stored range that starts after `value`.
+    fn first_starting_after(&self, value: T) -> usize {
+        self.ranges.partition_point(|&(start, _)| start <= value)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.ranges
+            .get(self.first_ending_at_or_after(value))
+            .is_some_and(|&(start, _)| start <= value)
+    }
+
+    /// The stored range containing `value`, if any.
+    pub fn range_containing(&self, value: T) -> Option<RangeInclusive<T>> {
+        self.ranges
+            .get(self.first_ending_at_or_after(value))
+            .filter(|&&(start, _)| start <= value)
+            .map(|&(start, end)| start..=end)
+    }
+
+    /// Add every value in `range`, merging with any ranges it overlaps or
+    /// touches.
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let (lo, hi) = range.into_inner();
+        if lo > hi {
+            return;
+        }
+        // Ranges ending just before `lo` or starting just after `hi` touch
+        // the new range and merge with it too.
+        let first = self.first_ending_at_or_after(lo.saturating_sub(T::one()));
+        let last = self.first_starting_after(hi.saturating_add(T::one()));
+        let (mut new_lo, mut new_hi) = (lo, hi);
+        if first < last {
+            new_lo = new_lo.min(self.ranges[first].0);
+            new_hi = new_hi.max(self.ranges[last - 1].1);
+        }
+        self.ranges.splice(first..last, [(new_lo, new_hi)]);
+    }
+
+    /// Remove every value in `range`, splitting any range it falls inside.
+    pub fn remove(&mut self, range: RangeInclusive<T>) {
+        let (lo, hi) = range.into_inner();
+        if lo > hi {
+            return;
+        }
+        let first = self.first_ending_at_or_after(lo);
+        let last = self.first_starting_after(hi);
+        if first >= last {
+            return;
+        }
+        let mut pieces = Vec::with_capacity(2);
+        if self.ranges[first].0 < lo {
+            pieces.push((self.ranges[first].0, lo - T::one()));
+        }
+        if self.ranges[last - 1].1 > hi {
+            pieces.push((hi + T::one(), self.ranges[last - 1].1));
+        }
+        self.ranges.splice(first..last, pieces);
+    }
+
+    /// The disjoint ranges of the set, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|&(start, end)| start..=end)
+    }
+
+    /// Every value in the set, in increasing order.
+    pub fn values(&self) -> impl Iterator<Item = T> + '_ {
+        self.ranges.iter().flat_map(|&(start, end)| {
+            std::iter::successors(Some(start), move |&v| if v < end { Some(v + T::one()) } else { None })
+        })
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut result = self.clone();
+        for range in other.iter() {
+            result.insert(range);
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a_start, a_end) = self.ranges[i];
+            let (b_start, b_end) = other.ranges[j];
+            let (start, end) = (a_start.max(b_start), a_end.min(b_end));
+            if start <= end {
+                ranges.push((start, end));
+            }
+            // Whichever range ends first can't meet anything further.
+            if a_end < b_end { i += 1 } else { j += 1 }
+        }
+        IntervalSet { ranges }
+    }
+
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut result = self.clone();
+        for range in other.iter() {
+            result.remove(range);
+        }
+        result
+    }
+
+    /// The values within `bounds` that are not in the set.
+    pub fn complement(&self, bounds: RangeInclusive<T>) -> IntervalSet<T> {
+        let (lo, hi) = bounds.into_inner();
+        let mut ranges = Vec::new();
+        // The next value that might be uncovered, or None once past `hi`.
+        let mut cursor = Some(lo).filter(|&lo| lo <= hi);
+        for &(start, end) in &self.ranges[self.first_ending_at_or_after(lo)..] {
+            let Some(from) = cursor else { break };
+            if start > hi {
+                break;
+            }
+            if start > from {
+                ranges.push((from, start - T::one()));
+            }
+            cursor = end.checked_add(&T::one()).filter(|&next| next <= hi);
+        }
+        if let Some(from) = cursor {
+            ranges.push((from, hi));
+        }
+        IntervalSet { ranges }
+    }
+}
+
+impl<T: PrimInt + Debug> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut result = IntervalSet::new();
+        for range in iter {
+            result.insert(range);
+        }
+        result
+    }
+}
+
+impl<T: PrimInt + Debug> Extend<RangeInclusive<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = RangeInclusive<T>>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert(range);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges<T: PrimInt + Debug>(set: &IntervalSet<T>) -> Vec<RangeInclusive<T>> {
+        set.iter().collect()
+    }
+
+    #[test]
+    fn test_insert_merges() {
+        // The ranges from the 2025 day 5 example.
+        let set: IntervalSet<usize> = [3..=5, 10..=14, 16..=20, 12..=18].into_iter().collect();
+        assert_eq!(ranges(&set), vec![3..=5, 10..=20]);
+        assert_eq!(set.total_length(), 14);
+        assert!(set.contains(5) && set.contains(17) && !set.contains(1) && !set.contains(32));
+        assert_eq!(set.range_containing(11), Some(10..=20));
+
+        let mut touching = IntervalSet::new();
+        touching.extend([1..=3, 7..=9, 4..=6]);
+        assert_eq!(ranges(&touching), vec![1i64..=9]);
+        touching.insert(RangeInclusive::new(5, 4));  // Empty; ignored.
+        assert_eq!(touching.range_count(), 1);
+    }
+
+    #[test]
+    fn test_remove_splits() {
+        let mut set: IntervalSet<i64> = [-10..=10, 20..=30].into_iter().collect();
+        set.remove(-2..=2);
+        set.remove(8..=22);
+        assert_eq!(ranges(&set), vec![-10..=-3, 3..=7, 23..=30]);
+        set.remove(-100..=100);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: IntervalSet<i64> = [0..=10, 20..=30].into_iter().collect();
+        let b: IntervalSet<i64> = [5..=25].into_iter().collect();
+        assert_eq!(ranges(&a.intersection(&b)), vec![5..=10, 20..=25]);
+        assert_eq!(ranges(&a.union(&b)), vec![0..=30]);
+        assert_eq!(ranges(&a.difference(&b)), vec![0..=4, 26..=30]);
+        assert_eq!(ranges(&a.complement(-5..=40)), vec![-5..=-1, 11..=19, 31..=40]);
+        assert_eq!(ranges(&a.complement(2..=8)), vec![]);
+        assert_eq!(ranges(&a.complement(12..=15)), vec![12..=15]);
+        let full: IntervalSet<i64> = [i64::MIN..=i64::MAX].into_iter().collect();
+        assert!(full.complement(i64::MIN..=i64::MAX).is_empty());
+        assert_eq!(a.values().take(3).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(a.values().count(), 22);
+    }
+}
@@ -0,0 +1,186 @@
+#![allow(dead_code)]
+//! Precomputed straight-line moves for walkers on a board.
+//!
+//! A walker that moves in a straight line until it bumps into an obstacle
+//! (and then turns) spends nearly all of its time crossing empty cells.  A
+//! `JumpTable` records, for every cell and direction, where such a walker
+//! stops, so that each straight run costs one lookup.  A single extra
+//! obstacle can be patched in without rebuilding the table, which is what
+//! "what if we added an obstacle here?" searches need.
+
+use std::collections::HashSet;
+use std::fmt::Debug;
+
+use super::grid_board::{Board, Direction, Xy};
+
+/// How a walker's route ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WalkOutcome {
+    /// The walker leaves the board from this cell.
+    Exits(Xy),
+    /// The walker repeats a turn it has made before, and so walks forever.
+    Loops,
+}
+
+#[derive(Clone)]
+pub struct JumpTable {
+    /// For each direction, in `Direction::ALL` order, the cell where a
+    /// walker starting at each cell stops, or `None` if it walks off the board.
+    stops: [Board<Option<Xy>>; 4],
+}
+
+fn direction_index(direction: Direction) -> usize {
+    Direction::ALL.iter().position(|&d| d == direction).unwrap()
+}
+
+impl JumpTable {
+    pub fn new<BoardContent>(board: &Board<BoardContent>,
+                             is_obstacle: impl Fn(BoardContent) -> bool) -> Self
+    where BoardContent: Copy + Debug + PartialEq,
+    {
+        let stops = Direction::ALL.map(|direction| {
+            let offset = direction.to_offset();
+            let mut stops: Board<Option<Xy>> = Board::new(board.height(), board.width(), None);
+            // Visit the cells furthest along `direction` first, so that each
+            // cell's stop can be copied from the next cell's.
+            let mut coords = board.all_coords();
+            coords.sort_by_key(|xy| -xy.dot(&offset));
+            for xy in coords {
+                let next = xy + offset;
+                let stop = match board.maybe_at(next) {
+                    None => None,
+                    Some(c) if is_obstacle(c) => Some(xy),
+                    Some(_) => stops.at(next),
+                };
+                stops.set_at(xy, stop);
+            }
+            stops
+        });
+        JumpTable { stops }
+    }
+
+    /// Where a walker starting at `from` and moving in `direction` stops in
+    /// front of an obstacle (possibly `from` itself), or `None` if it walks
+    /// off the board.
+    pub fn jump(&self, from: Xy, direction: Direction) -> Option<Xy> {
+        self.stops[direction_index(direction)].at(from)
+    }
+
+    /// This table with one more obstacle at `extra`.
+    pub fn with_obstacle(&self, extra: Xy) -> PatchedJumpTable<'_> {
+        PatchedJumpTable { table: self, extra }
+    }
+
+    /// Follow a walker that turns clockwise at every obstacle.
+    pub fn walk(&self, start: Xy, direction: Direction) -> WalkOutcome {
+        walk_turning(|from, d| self.jump(from, d), self.stops[0].dimensions(), start, direction)
+    }
+}
+
+/// A `JumpTable` with one extra obstacle, answering the same queries.
+#[derive(Clone, Copy)]
+pub struct PatchedJumpTable<'a> {
+    table: &'a JumpTable,
+    extra: Xy,
+}
+
+impl PatchedJumpTable<'_> {
+    pub fn jump(&self, from: Xy, direction: Direction) -> Option<Xy> {
+        let stop = self.table.jump(from, direction);
+        let offset = direction.to_offset();
+        let to_extra = self.extra - from;
+        // The extra obstacle matters only if it is straight ahead and no
+        // further than the cell where the walker would otherwise stop.
+        let ahead = to_extra.dot(&offset);
+        let in_line = to_extra == offset * ahead;
+        let before_stop = stop.is_none_or(|stop| ahead <= (stop - from).dot(&offset));
+        if in_line && ahead > 0 && before_stop {
+            Some(self.extra - offset)
+        } else {
+            stop
+        }
+    }
+
+    /// Follow a walker that turns clockwise at every obstacle.
+    pub fn walk(&self, start: Xy, direction: Direction) -> WalkOutcome {
+        walk_turning(|from, d| self.jump(from, d), self.table.stops[0].dimensions(),
+                     start, direction)
+    }
+}
+
+/// Walk from jump to jump, turning clockwise after each, until the walker
+/// leaves the board or repeats a turn.
+fn walk_turning(jump: impl Fn(Xy, Direction) -> Option<Xy>, dimensions: Xy,
+                start: Xy, direction: Direction) -> WalkOutcome {
+    let mut turns: HashSet<(Xy, Direction)> = HashSet::new();
+    let (mut position, mut direction) = (start, direction);
+    loop {
+        match jump(position, direction) {
+            None => {
+                // Run straight to the edge of the board.
+                let offset = direction.to_offset();
+                while (0..2).all(|i| (0..dimensions[i]).contains(&(position + offset)[i])) {
+                    position += offset;
+                }
+                return WalkOutcome::Exits(position);
+            }
+            Some(stop) => {
+                if !turns.insert((stop, direction)) {
+                    return WalkOutcome::Loops;
+                }
+                position = stop;
+                direction = direction.cw();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn room() -> Board<char> {
+        Board::from_strings(&[
+            "....#.....",
+            ".........#",
+            "..........",
+            "..#.......",
+            ".......#..",
+            "..........",
+            ".#........",
+            "........#.",
+            "#.........",
+            "......#...",
+        ])
+    }
+
+    #[test]
+    fn test_jumps() {
+        let table = JumpTable::new(&room(), |c| c == '#');
+        let start = Xy::new(4, 6);
+        assert_eq!(table.jump(start, Direction::North), Some(Xy::new(4, 1)));
+        assert_eq!(table.jump(Xy::new(4, 1), Direction::East), Some(Xy::new(8, 1)));
+        assert_eq!(table.jump(Xy::new(0, 0), Direction::West), None);
+        assert_eq!(table.jump(Xy::new(3, 0), Direction::East), Some(Xy::new(3, 0)));
+        assert_eq!(table.walk(start, Direction::North), WalkOutcome::Exits(Xy::new(7, 9)));
+
+        let patched = table.with_obstacle(Xy::new(4, 3));
+        assert_eq!(patched.jump(start, Direction::North), Some(Xy::new(4, 4)));
+        assert_eq!(patched.jump(start, Direction::South), table.jump(start, Direction::South));
+        assert_eq!(patched.jump(Xy::new(4, 2), Direction::North), Some(Xy::new(4, 1)));
+    }
+
+    #[test]
+    fn test_patched_loops() {
+        // The six obstacle positions from the 2024 day 6 example that trap
+        // the guard, and one that does not.
+        let table = JumpTable::new(&room(), |c| c == '#');
+        let start = Xy::new(4, 6);
+        for (x, y) in [(3, 6), (6, 7), (7, 7), (1, 8), (3, 8), (7, 9)] {
+            assert_eq!(table.with_obstacle(Xy::new(x, y)).walk(start, Direction::North),
+                       WalkOutcome::Loops, "obstacle at {},{}", x, y);
+        }
+        assert_ne!(table.with_obstacle(Xy::new(5, 5)).walk(start, Direction::North),
+                   WalkOutcome::Loops);
+    }
+}
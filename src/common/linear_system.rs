@@ -0,0 +1,209 @@
+#![allow(dead_code)]
+//! Exact solutions of small integer linear systems.
+//!
+//! Puzzles that reduce to `A x = b` usually want integer answers, and their
+//! numbers are often too large for floating point to be trusted once
+//! inverted.  Here the system is reduced by Gaussian elimination over
+//! `i128` rationals, so every step is exact and the caller can ask directly
+//! whether the answer is integral and non-negative.
+
+use num::{ToPrimitive, Zero};
+use num_rational::Ratio;
+
+pub type Rational = Ratio<i128>;
+
+/// The solutions of `A x = b`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution {
+    /// No `x` satisfies every equation.
+    Inconsistent,
+    Unique(Vec<Rational>),
+    /// Every `particular + sum(t_i * null_space[i])` is a solution, for any
+    /// rational `t_i`.  Each null space vector has a 1 in the position of
+    /// one free variable and a 0 in the others'.
+    Infinite { particular: Vec<Rational>, null_space: Vec<Vec<Rational>>, free: Vec<usize> },
+}
+
+/// Solve `a x = b` exactly, where `a` has one row per equation.
+pub fn solve(a: &[Vec<i64>], b: &[i64]) -> Solution {
+    assert_eq!(a.len(), b.len(), "Need one right hand side per equation");
+    let columns = a.first().map_or(0, |row| row.len());
+    assert!(a.iter().all(|row| row.len() == columns), "Ragged coefficient matrix");
+
+    // The augmented matrix [a | b], reduced to reduced row echelon form.
+    let mut m: Vec<Vec<Rational>> = a
+        .iter()
+        .zip(b)
+        .map(|(row, &rhs)| row.iter().chain([&rhs]).map(|&v| Rational::from_integer(v as i128)).collect())
+        .collect();
+    let mut pivots: Vec<usize> = Vec::new();
+    for col in 0..columns {
+        let row = pivots.len();
+        let Some(pivot) = (row..m.len()).find(|&r| !m[r][col].is_zero()) else { continue };
+        m.swap(row, pivot);
+        let scale = m[row][col];
+        for v in m[row].iter_mut() {
+            *v /= scale;
+        }
+        let pivot_row = m[row].clone();
+        for (r, other) in m.iter_mut().enumerate() {
+            if r != row && !other[col].is_zero() {
+                let factor = other[col];
+                for (v, &p) in other.iter_mut().zip(&pivot_row).skip(col) {
+                    *v -= factor * p;
+                }
+            }
+        }
+        pivots.push(col);
+    }
+
+    // A row reading 0 = nonzero means there is no solution.
+    if m[pivots.len()..].iter().any(|row| !row[columns].is_zero()) {
+        return Solution::Inconsistent;
+    }
+    let mut particular = vec![Rational::zero(); columns];
+    for (row, &col) in pivots.iter().enumerate() {
+        particular[col] = m[row][columns];
+    }
+    if pivots.len() == columns {
+        return Solution::Unique(particular);
+    }
+    let free: Vec<usize> = (0..columns).filter(|c| !pivots.contains(c)).collect();
+    let null_space = free
+        .iter()
+        .map(|&f| {
+            let mut v = vec![Rational::zero(); columns];
+            v[f] = Rational::from_integer(1);
+            for (row, &col) in pivots.iter().enumerate() {
+                v[col] = -m[row][f];
+            }
+            v
+        })
+        .collect();
+    Solution::Infinite { particular, null_space, free }
+}
+
+/// `v` as integers, if every entry is an integer that fits in an `i64`.
+fn to_integers(v: &[Rational]) -> Option<Vec<i64>> {
+    v.iter().map(|r| if r.is_integer() { r.to_integer().to_i64() } else { None }).collect()
+}
+
+impl Solution {
+    /// The unique solution, if there is one and it is all integers.
+    pub fn unique_integer(&self) -> Option<Vec<i64>> {
+        match self {
+            Solution::Unique(x) => to_integers(x),
+            _ => None,
+        }
+    }
+
+    /// The unique solution, if there is one and it is all non-negative
+    /// integers.
+    pub fn unique_nonnegative_integer(&self) -> Option<Vec<i64>> {
+        self.unique_integer().filter(|x| x.iter().all(|&v| v >= 0))
+    }
+
+    /// Every all-integer, all-non-negative solution whose free variables are
+    /// at most `bound`.  For a unique solution `bound` is ignored.  This tries
+    /// every combination of free variables, `(bound + 1) ^ free` of them, so
+    /// it is only for small bounds.
+    pub fn nonnegative_integer_solutions(&self, bound: i64) -> Vec<Vec<i64>> {
+        match self {
+            Solution::Inconsistent => Vec::new(),
+            Solution::Unique(_) => self.unique_nonnegative_integer().into_iter().collect(),
+            Solution::Infinite { particular, null_space, .. } => {
+                let mut result = Vec::new();
+                let mut ts = vec![0i64; null_space.len()];
+                loop {
+                    let x: Vec<Rational> = (0..particular.len())
+                        .map(|i| particular[i] + null_space.iter().zip(&ts)
+                             .map(|(v, &t)| v[i] * Rational::from_integer(t as i128))
+                             .sum::<Rational>())
+                        .collect();
+                    if let Some(x) = to_integers(&x).filter(|x| x.iter().all(|&v| v >= 0)) {
+                        result.push(x);
+                    }
+                    // Advance the free variables like an odometer.
+                    let Some(i) = ts.iter().position(|&t| t < bound) else { break };
+                    ts[i] += 1;
+                    ts[..i].iter_mut().for_each(|t| *t = 0);
+                }
+                result
+            }
+        }
+    }
+
+    /// Whether some solution is all non-negative integers, searching free
+    /// variables up to `bound` for singular systems.
+    pub fn has_nonnegative_integer_solution(&self, bound: i64) -> bool {
+        !self.nonnegative_integer_solutions(bound).is_empty()
+    }
+}
+
+/// The determinant of a small square integer matrix, exactly.
+pub fn determinant(a: &[Vec<i64>]) -> Rational {
+    let n = a.len();
+    let mut m: Vec<Vec<Rational>> = a
+        .iter()
+        .map(|row| {
+            assert_eq!(row.len(), n, "Determinant needs a square matrix");
+            row.iter().map(|&v| Rational::from_integer(v as i128)).collect()
+        })
+        .collect();
+    let mut det = Rational::from_integer(1);
+    for col in 0..n {
+        let Some(pivot) = (col..n).find(|&r| !m[r][col].is_zero()) else { return Rational::zero() };
+        if pivot != col {
+            m.swap(pivot, col);
+            det = -det;
+        }
+        det *= m[col][col];
+        let pivot_row = m[col].clone();
+        for other in m.iter_mut().skip(col + 1) {
+            let factor = other[col] / pivot_row[col];
+            for (v, &p) in other.iter_mut().zip(&pivot_row).skip(col) {
+                *v -= factor * p;
+            }
+        }
+    }
+    det
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn r(n: i128, d: i128) -> Rational {
+        Rational::new(n, d)
+    }
+
+    #[test]
+    fn test_unique() {
+        // A claw machine from 2024 day 13, with the part B offset.
+        let a = vec![vec![26, 67], vec![66, 21]];
+        let b = [10000000012748, 10000000012176];
+        let solution = solve(&a, &b);
+        assert_eq!(solution.unique_nonnegative_integer(), Some(vec![118679050709, 103199174542]));
+
+        let half = solve(&[vec![2, 0], vec![0, 4]], &[1, -2]);
+        assert_eq!(half, Solution::Unique(vec![r(1, 2), r(-1, 2)]));
+        assert_eq!(half.unique_integer(), None);
+        assert_eq!(determinant(&[vec![2, 0], vec![0, 4]]), r(8, 1));
+        assert_eq!(determinant(&[vec![0, 1], vec![1, 0]]), r(-1, 1));
+    }
+
+    #[test]
+    fn test_singular() {
+        assert_eq!(solve(&[vec![1, 2], vec![2, 4]], &[3, 7]), Solution::Inconsistent);
+        assert_eq!(determinant(&[vec![1, 2], vec![2, 4]]), r(0, 1));
+
+        // x + 2y = 6 has (6, 0), (4, 1), (2, 2), (0, 3) in non-negative integers.
+        let line = solve(&[vec![1, 2], vec![2, 4]], &[6, 12]);
+        let Solution::Infinite { ref free, .. } = line else { panic!("{:?}", line) };
+        assert_eq!(free, &vec![1]);
+        assert_eq!(line.nonnegative_integer_solutions(10),
+                   vec![vec![6, 0], vec![4, 1], vec![2, 2], vec![0, 3]]);
+        assert!(line.has_nonnegative_integer_solution(10));
+        assert!(!solve(&[vec![2, 4]], &[3]).has_nonnegative_integer_solution(10));
+    }
+}
@@ -1,3 +1,24 @@
 pub mod grid_board;
 pub mod parsing;
 pub mod arraylike;
+pub mod pattern_search;
+pub mod automaton;
+pub mod board_image;
+pub mod board_viewer;
+pub mod tile;
+pub mod board_diff;
+pub mod bit_board;
+pub mod rect_grid;
+pub mod hex_grid;
+pub mod voxel;
+pub mod astar;
+pub mod graph_search;
+pub mod cycle;
+pub mod jump_table;
+pub mod interval_set;
+pub mod linear_system;
+pub mod number_theory;
+pub mod digits;
+pub mod expression_solver;
+pub mod multiset_evolution;
+pub mod union_find;
@@ -0,0 +1,187 @@
+#![allow(dead_code)]
+//! Populations of states that each turn into several states per generation.
+//!
+//! When every state evolves independently and only the number of states
+//! matters, not their order, the population can be kept as a multiset: one
+//! entry per distinct state with the number of copies.  Puzzles like this
+//! tend to have few distinct states but exponentially many copies, so each
+//! generation costs one rule application per distinct state, the rule's
+//! results are cached, and counts are checked so that an overflow is
+//! reported rather than wrapped.  Use `num::BigUint` counts when even `u64`
+//! is too small.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use num::{CheckedAdd, One, Zero};
+
+/// Bounds shared by the counts of every multiset in this module.
+pub trait Count: Clone + Zero + One + CheckedAdd {}
+impl<T> Count for T where T: Clone + Zero + One + CheckedAdd {}
+
+/// Distinct states with the number of copies of each.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Multiset<S: Eq + Hash, C = u64> {
+    counts: HashMap<S, C>,
+}
+
+impl<S: Eq + Hash, C: Count> Default for Multiset<S, C> {
+    fn default() -> Self {
+        Multiset { counts: HashMap::new() }
+    }
+}
+
+impl<S: Eq + Hash + Clone, C: Count> Multiset<S, C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `count` copies of `state`, or return `None` if its count would
+    /// overflow.
+    pub fn checked_insert(&mut self, state: S, count: C) -> Option<()> {
+        let entry = self.counts.entry(state).or_insert_with(C::zero);
+        *entry = entry.checked_add(&count)?;
+        Some(())
+    }
+
+    /// Add `count` copies of `state`.  Panics on overflow.
+    pub fn insert(&mut self, state: S, count: C) {
+        self.checked_insert(state, count).expect("Multiset count overflowed");
+    }
+
+    /// The number of copies of `state`.
+    pub fn count(&self, state: &S) -> C {
+        self.counts.get(state).cloned().unwrap_or_else(C::zero)
+    }
+
+    /// The number of distinct states.
+    pub fn distinct(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// The total number of copies of every state, or `None` on overflow.
+    pub fn checked_population(&self) -> Option<C> {
+        self.counts.values().try_fold(C::zero(), |total, count| total.checked_add(count))
+    }
+
+    /// The total number of copies of every state.  Panics on overflow.
+    pub fn population(&self) -> C {
+        self.checked_population().expect("Multiset population overflowed")
+    }
+
+    /// Each distinct state with its count, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&S, &C)> {
+        self.counts.iter()
+    }
+}
+
+impl<S: Eq + Hash + Clone, C: Count> FromIterator<S> for Multiset<S, C> {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut result = Multiset::new();
+        for state in iter {
+            result.insert(state, C::one());
+        }
+        result
+    }
+}
+
+/// The result of evolving a multiset for some generations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Evolution<S: Eq + Hash, C> {
+    pub last: Multiset<S, C>,
+    /// The population before the first generation and after each one.
+    pub populations: Vec<C>,
+}
+
+type RuleFn<'a, S> = Box<dyn Fn(&S) -> Vec<S> + 'a>;
+
+/// Applies a one-to-many rule to whole multisets, remembering what each
+/// state turned into.
+pub struct Evolver<'a, S> {
+    rule: RuleFn<'a, S>,
+    expansions: HashMap<S, Vec<S>>,
+}
+
+impl<'a, S: Eq + Hash + Clone> Evolver<'a, S> {
+    /// `rule` lists the states one copy of a state becomes after a
+    /// generation, with repeats for several copies.
+    pub fn new(rule: impl Fn(&S) -> Vec<S> + 'a) -> Self {
+        Evolver { rule: Box::new(rule), expansions: HashMap::new() }
+    }
+
+    /// What `state` becomes after one generation.
+    pub fn expand(&mut self, state: &S) -> &[S] {
+        if !self.expansions.contains_key(state) {
+            let children = (self.rule)(state);
+            self.expansions.insert(state.clone(), children);
+        }
+        &self.expansions[state]
+    }
+
+    /// The number of distinct states whose expansions are cached.
+    pub fn cached(&self) -> usize {
+        self.expansions.len()
+    }
+
+    /// The multiset after one generation, or `None` if a count overflows.
+    pub fn step<C: Count>(&mut self, population: &Multiset<S, C>) -> Option<Multiset<S, C>> {
+        let mut next = Multiset::new();
+        for (state, count) in population.iter() {
+            for child in self.expand(state) {
+                next.checked_insert(child.clone(), count.clone())?;
+            }
+        }
+        Some(next)
+    }
+
+    /// Evolve `start` for `generations` generations, or return `None` if a
+    /// count overflows.
+    pub fn run<C: Count>(&mut self, start: Multiset<S, C>, generations: usize) -> Option<Evolution<S, C>> {
+        let mut populations = vec![start.checked_population()?];
+        let mut last = start;
+        for _ in 0..generations {
+            last = self.step(&last)?;
+            populations.push(last.checked_population()?);
+        }
+        Some(Evolution { last, populations })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use num::BigUint;
+
+    #[test]
+    fn test_doubling() {
+        // Every cell splits in two, and every third generation's cells also
+        // spawn a spore.
+        let mut evolver = Evolver::new(|&age: &u32| {
+            let mut children = vec![age + 1, age + 1];
+            if age % 3 == 2 {
+                children.push(0);
+            }
+            children
+        });
+        let start: Multiset<u32> = [0, 0, 1].into_iter().collect();
+        assert_eq!(start.distinct(), 2);
+        assert_eq!(start.count(&0), 2);
+        let evolution = evolver.run(start.clone(), 3).unwrap();
+        assert_eq!(evolution.populations, vec![3, 6, 14, 36]);
+        assert_eq!(evolution.last.count(&0), 8);
+        assert_eq!(evolution.last.count(&3), 16);
+        assert_eq!(evolver.cached(), 4);
+
+        // 2 ^ 70 copies overflow a u64 but not a BigUint.
+        let only_doubling = |&s: &u32| vec![s, s];
+        assert!(Evolver::new(only_doubling).run(start, 70).is_none());
+        let big: Multiset<u32, BigUint> = [7].into_iter().collect();
+        let evolution = Evolver::new(only_doubling).run(big, 70).unwrap();
+        assert_eq!(evolution.last.population(), BigUint::from(2u32).pow(70));
+    }
+}
@@ -0,0 +1,162 @@
+#![allow(dead_code)]
+//! Integer and modular arithmetic.
+//!
+//! Everything here is generic over signed `num::Integer` types, so the same
+//! functions serve `i64`, `i128` and `num::BigInt`.  Residues are always
+//! returned in `[0, m)`, whatever the signs of the inputs.
+
+use num::{Integer, Signed};
+
+/// The greatest common divisor of all of `values`; zero if there are none.
+pub fn gcd_all<T: Integer + Clone>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::zero(), |acc, v| acc.gcd(&v))
+}
+
+/// The least common multiple of all of `values`; one if there are none.
+pub fn lcm_all<T: Integer + Clone>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::one(), |acc, v| acc.lcm(&v))
+}
+
+/// `(g, x, y)` with `g = gcd(a, b) >= 0` and `a * x + b * y = g`.
+pub fn extended_gcd<T: Integer + Signed + Clone>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::one(), T::zero());
+    let (mut old_y, mut y) = (T::zero(), T::one());
+    while !r.is_zero() {
+        let q = old_r.div_floor(&r);
+        (old_r, r) = (r.clone(), old_r - q.clone() * r);
+        (old_x, x) = (x.clone(), old_x - q.clone() * x);
+        (old_y, y) = (y.clone(), old_y - q * y);
+    }
+    if old_r.is_negative() {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// `a` reduced into `[0, m)`.
+pub fn modulo<T: Integer + Signed + Clone>(a: T, m: &T) -> T {
+    a.mod_floor(&m.abs())
+}
+
+/// The `x` in `[0, m)` with `a * x = 1 (mod m)`, if `a` and `m` are coprime.
+pub fn mod_inverse<T: Integer + Signed + Clone>(a: T, m: T) -> Option<T> {
+    let (g, x, _) = extended_gcd(modulo(a, &m), m.clone());
+    if g.is_one() { Some(modulo(x, &m)) } else { None }
+}
+
+/// `base ^ exp (mod m)` by repeated squaring.  `exp` must be non-negative.
+pub fn mod_pow<T: Integer + Signed + Clone>(base: T, exp: T, m: T) -> T {
+    assert!(!exp.is_negative(), "Negative exponent; use mod_inverse first");
+    let two = T::one() + T::one();
+    let mut result = modulo(T::one(), &m);
+    let mut base = modulo(base, &m);
+    let mut exp = exp;
+    while !exp.is_zero() {
+        if exp.is_odd() {
+            result = modulo(result * base.clone(), &m);
+        }
+        base = modulo(base.clone() * base, &m);
+        exp = exp / two.clone();
+    }
+    result
+}
+
+/// The Chinese Remainder Theorem:  the `(x, m)` such that the solutions of
+/// every `x = r_i (mod m_i)` are exactly `x (mod m)`, with `x` in `[0, m)`.
+/// The moduli need not be coprime; `None` means the congruences conflict.
+pub fn crt<T: Integer + Signed + Clone>(congruences: impl IntoIterator<Item = (T, T)>) -> Option<(T, T)> {
+    let mut x = T::zero();
+    let mut m = T::one();
+    for (r, n) in congruences {
+        let n = n.abs();
+        let r = modulo(r, &n);
+        // Solve x + m * k = r (mod n) for k.
+        let (g, p, _) = extended_gcd(m.clone(), n.clone());
+        let difference = r - x.clone();
+        if !difference.is_multiple_of(&g) {
+            return None;
+        }
+        let step = n.clone() / g.clone();
+        let k = modulo((difference / g) * p, &step);
+        let combined = m.clone() * step;
+        x = modulo(x + m * k, &combined);
+        m = combined;
+    }
+    Some((x, m))
+}
+
+/// Every positive divisor of `n`, in increasing order.
+pub fn divisors<T: Integer + Signed + Clone>(n: T) -> Vec<T> {
+    let n = n.abs();
+    let mut small = Vec::new();
+    let mut large = Vec::new();
+    let mut d = T::one();
+    while d.clone() * d.clone() <= n {
+        if n.is_multiple_of(&d) {
+            let pair = n.clone() / d.clone();
+            if pair != d {
+                large.push(pair);
+            }
+            small.push(d.clone());
+        }
+        d = d + T::one();
+    }
+    small.extend(large.into_iter().rev());
+    small
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use num::BigInt;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd_all([12i64, 18, -30]), 6);
+        assert_eq!(lcm_all([4i64, 6, 10]), 60);
+        assert_eq!(gcd_all(Vec::<i64>::new()), 0);
+        assert_eq!(lcm_all([101i128, 103]), 10403);
+        for (a, b) in [(240i64, 46), (-7, 3), (0, 5), (17, 0), (-4, -6)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, a.gcd(&b));
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(modulo(-3i64, &100), 97);
+        assert_eq!(mod_inverse(3i64, 11), Some(4));
+        assert_eq!(mod_inverse(-3i64, 11), Some(7));
+        assert_eq!(mod_inverse(6i64, 9), None);
+        assert_eq!(mod_pow(2i64, 10, 1000), 24);
+        assert_eq!(mod_pow(7i64, 0, 1), 0);
+        let big = mod_pow(BigInt::from(3), BigInt::from(1000), BigInt::from(10).pow(30));
+        assert_eq!(big, BigInt::from(3).pow(1000u32) % BigInt::from(10).pow(30));
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt([(2i64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // Non-coprime moduli, compatible and not.
+        assert_eq!(crt([(2i64, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt([(1i64, 4), (2, 6)]), None);
+        assert_eq!(crt(Vec::<(i128, i128)>::new()), Some((0, 1)));
+        let (x, m) = crt([(BigInt::from(-1), BigInt::from(1_000_000_007)),
+                          (BigInt::from(5), BigInt::from(998_244_353))]).unwrap();
+        assert_eq!(m, BigInt::from(1_000_000_007i64 * 998_244_353));
+        assert_eq!(modulo(x.clone() + 1, &BigInt::from(1_000_000_007)), BigInt::from(0));
+        assert_eq!(x % BigInt::from(998_244_353), BigInt::from(5));
+    }
+
+    #[test]
+    fn test_divisors() {
+        assert_eq!(divisors(36i64), vec![1, 2, 3, 4, 6, 9, 12, 18, 36]);
+        assert_eq!(divisors(-7i128), vec![1, 7]);
+        assert_eq!(divisors(1i64), vec![1]);
+        assert!(divisors(0i64).is_empty());
+    }
+}
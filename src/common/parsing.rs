@@ -106,24 +106,6 @@ pub fn parse_as_i32s(input: Vec<Vec<String>>) -> Vec<Vec<i64>> {
     result
 }
 
-/// Extracts characters from `input` starting at `start` and then every `stride` characters thereafter.
-pub fn stride_text(input: &str, start: usize, stride: usize) -> String {
-    let mut line_iter = input.chars();
-    let mut line = String::new();
-    line += &line_iter.nth(start).unwrap().to_string();
-    loop {
-        match line_iter.nth(stride - 1) {
-            None => {
-                break;
-            }
-            Some(c) => {
-                line += &c.to_string();
-            }
-        }
-    }
-    line
-}
-
 pub fn read_grid_board<BoardContent>(
     source: Option<String>,
 ) -> Board<BoardContent> 
@@ -0,0 +1,354 @@
+#![allow(dead_code)]
+//! Fast search of a board for several wildcard "needle" boards at once.
+//!
+//! For every distinct value mentioned by any needle we build a bitmask of the
+//! haystack cells holding that value, one bit per cell, packed into `u64`
+//! words per row.  A needle then matches at every position where the AND of
+//! its (shifted) cell masks is set, so each needle cell costs one pass over
+//! the haystack at 64 cells per operation rather than one comparison per
+//! cell per offset.  Wildcard (`None`) cells cost nothing.
+
+use std::fmt::Debug;
+
+use super::grid_board::{Board, Scalar, Xy};
+
+const WORD_BITS: usize = 64;
+
+/// The eight rotations and reflections of a rectangle (the dihedral group D4).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    Identity,
+    /// A quarter turn clockwise.
+    Rot90,
+    Rot180,
+    /// A quarter turn counterclockwise.
+    Rot270,
+    /// Mirror left-to-right.
+    FlipX,
+    /// Mirror top-to-bottom.
+    FlipY,
+    /// Mirror across the main (upper left to lower right) diagonal.
+    Transpose,
+    /// Mirror across the anti-diagonal.
+    AntiTranspose,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rot90,
+        Symmetry::Rot180,
+        Symmetry::Rot270,
+        Symmetry::FlipX,
+        Symmetry::FlipY,
+        Symmetry::Transpose,
+        Symmetry::AntiTranspose,
+    ];
+
+    /// Whether this symmetry exchanges width and height.
+    pub fn swaps_axes(self) -> bool {
+        matches!(self, Symmetry::Rot90 | Symmetry::Rot270
+                 | Symmetry::Transpose | Symmetry::AntiTranspose)
+    }
+
+    /// Where the cell at `xy` of a `dimensions`-sized board lands under this
+    /// symmetry.
+    pub fn map_xy(self, xy: Xy, dimensions: Xy) -> Xy {
+        let (x, y) = (xy[0], xy[1]);
+        let (w, h) = (dimensions[0], dimensions[1]);
+        match self {
+            Symmetry::Identity => Xy::new(x, y),
+            Symmetry::Rot90 => Xy::new(h - 1 - y, x),
+            Symmetry::Rot180 => Xy::new(w - 1 - x, h - 1 - y),
+            Symmetry::Rot270 => Xy::new(y, w - 1 - x),
+            Symmetry::FlipX => Xy::new(w - 1 - x, y),
+            Symmetry::FlipY => Xy::new(x, h - 1 - y),
+            Symmetry::Transpose => Xy::new(y, x),
+            Symmetry::AntiTranspose => Xy::new(h - 1 - y, w - 1 - x),
+        }
+    }
+
+    /// A copy of `board` transformed by this symmetry.  An empty board is
+    /// returned unchanged.
+    pub fn apply<BoardContent>(self, board: &Board<BoardContent>) -> Board<BoardContent>
+    where BoardContent: Copy + Debug + PartialEq,
+    {
+        if board.board.first().is_none_or(|row| row.is_empty()) {
+            return board.clone();
+        }
+        let dimensions = board.dimensions();
+        let (new_w, new_h) = if self.swaps_axes() {
+            (board.height(), board.width())
+        } else {
+            (board.width(), board.height())
+        };
+        let mut result = Board::new(new_h, new_w, board.at(Xy::new(0, 0)));
+        for (xy, c) in board.as_view().iter() {
+            result.set_at(self.map_xy(xy, dimensions), c);
+        }
+        result
+    }
+}
+
+/// One occurrence of a needle found by `PatternSearcher::search`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PatternMatch {
+    /// Index of the needle in the list the searcher was built from.
+    pub needle: usize,
+    /// The orientation of the needle that matched.
+    pub symmetry: Symmetry,
+    /// Upper left corner of the (transformed) needle on the haystack.
+    pub ul: Xy,
+}
+
+/// A compiled set of wildcard needles that can be searched for together.
+pub struct PatternSearcher<BoardContent> {
+    /// (needle index, orientation, transformed needle)
+    variants: Vec<(usize, Symmetry, Board<Option<BoardContent>>)>,
+    /// Every distinct non-wildcard value appearing in any variant.
+    values: Vec<BoardContent>,
+}
+
+impl<BoardContent> PatternSearcher<BoardContent>
+where BoardContent: Copy + Debug + PartialEq,
+{
+    /// A searcher for `needles` exactly as given.
+    pub fn new(needles: &[Board<Option<BoardContent>>]) -> Self {
+        Self::with_symmetries(needles, &[Symmetry::Identity])
+    }
+
+    /// A searcher for every rotation and reflection of `needles`.  Orientations
+    /// of a needle that are identical to an earlier orientation of the same
+    /// needle are only reported once, under the earlier symmetry.
+    pub fn with_all_symmetries(needles: &[Board<Option<BoardContent>>]) -> Self {
+        Self::with_symmetries(needles, &Symmetry::ALL)
+    }
+
+    /// A searcher for `needles` under each of the given `symmetries`.  Empty
+    /// needles never match.
+    pub fn with_symmetries(needles: &[Board<Option<BoardContent>>],
+                           symmetries: &[Symmetry]) -> Self {
+        let mut variants: Vec<(usize, Symmetry, Board<Option<BoardContent>>)> = Vec::new();
+        let mut values: Vec<BoardContent> = Vec::new();
+        for (needle_index, needle) in needles.iter().enumerate() {
+            if needle.board.first().is_none_or(|row| row.is_empty()) {
+                continue;
+            }
+            let first_variant = variants.len();
+            for &symmetry in symmetries {
+                let variant = symmetry.apply(needle);
+                if variants[first_variant..].iter().any(|(_, _, v)| *v == variant) {
+                    continue;
+                }
+                for (_, cell) in variant.as_view().iter() {
+                    if let Some(value) = cell {
+                        if !values.contains(&value) {
+                            values.push(value);
+                        }
+                    }
+                }
+                variants.push((needle_index, symmetry, variant));
+            }
+        }
+        PatternSearcher { variants, values }
+    }
+
+    /// Per-value bitmasks of the haystack:  `masks[v][y][w]` holds bit `b` iff
+    /// `haystack[y][w * 64 + b] == values[v]`.
+    fn value_masks(&self, haystack: &Board<BoardContent>) -> Vec<Vec<Vec<u64>>> {
+        let words = haystack.width().div_ceil(WORD_BITS);
+        let mut masks = vec![vec![vec![0u64; words]; haystack.height()]; self.values.len()];
+        for (y, row) in haystack.board.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if let Some(v) = self.values.iter().position(|value| value == cell) {
+                    masks[v][y][x / WORD_BITS] |= 1 << (x % WORD_BITS);
+                }
+            }
+        }
+        masks
+    }
+
+    /// Bits `[word * 64 + shift, word * 64 + shift + 64)` of `row`.
+    fn shifted_word(row: &[u64], word: usize, shift: usize) -> u64 {
+        let src = word + shift / WORD_BITS;
+        let bit = shift % WORD_BITS;
+        let lo = row.get(src).copied().unwrap_or(0);
+        if bit == 0 {
+            lo
+        } else {
+            let hi = row.get(src + 1).copied().unwrap_or(0);
+            (lo >> bit) | (hi << (WORD_BITS - bit))
+        }
+    }
+
+    /// Match bitmasks for one variant; row `y` bit `x` is set iff the variant
+    /// matches with its upper left corner at `(x, y)`.
+    fn variant_matches(&self,
+                       variant: &Board<Option<BoardContent>>,
+                       masks: &[Vec<Vec<u64>>],
+                       haystack_dimensions: Xy) -> Option<Vec<Vec<u64>>> {
+        let result_dimensions = haystack_dimensions - variant.dimensions() + Xy::new(1, 1);
+        if result_dimensions[0] <= 0 || result_dimensions[1] <= 0 {
+            return None;
+        }
+        let (result_w, result_h) = (result_dimensions[0] as usize, result_dimensions[1] as usize);
+        let words = result_w.div_ceil(WORD_BITS);
+        let mut result = vec![vec![!0u64; words]; result_h];
+        if result_w % WORD_BITS != 0 {
+            for row in result.iter_mut() {
+                row[words - 1] = (1 << (result_w % WORD_BITS)) - 1;
+            }
+        }
+        for (needle_xy, cell) in variant.as_view().iter() {
+            let Some(value) = cell else { continue };
+            let v = self.values.iter().position(|known| *known == value).unwrap();
+            let (dx, dy) = (needle_xy[0] as usize, needle_xy[1] as usize);
+            for (y, row) in result.iter_mut().enumerate() {
+                let mask_row = &masks[v][y + dy];
+                for (word, bits) in row.iter_mut().enumerate() {
+                    *bits &= Self::shifted_word(mask_row, word, dx);
+                }
+            }
+        }
+        Some(result)
+    }
+
+    /// Every match of every needle orientation on `haystack`, grouped by
+    /// needle orientation and then in row-major order.
+    pub fn search(&self, haystack: &Board<BoardContent>) -> Vec<PatternMatch> {
+        let masks = self.value_masks(haystack);
+        let mut result = Vec::new();
+        for (needle, symmetry, variant) in &self.variants {
+            let Some(matches) = self.variant_matches(variant, &masks, haystack.dimensions())
+            else { continue };
+            for (y, row) in matches.iter().enumerate() {
+                for (word, &bits) in row.iter().enumerate() {
+                    let mut bits = bits;
+                    while bits != 0 {
+                        let x = word * WORD_BITS + bits.trailing_zeros() as usize;
+                        bits &= bits - 1;
+                        result.push(PatternMatch {
+                            needle: *needle,
+                            symmetry: *symmetry,
+                            ul: Xy::new(x as Scalar, y as Scalar),
+                        });
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// The number of matches of every needle orientation on `haystack`.
+    pub fn count(&self, haystack: &Board<BoardContent>) -> usize {
+        let masks = self.value_masks(haystack);
+        self.variants
+            .iter()
+            .filter_map(|(_, _, variant)|
+                self.variant_matches(variant, &masks, haystack.dimensions()))
+            .flatten()
+            .flatten()
+            .map(|bits| bits.count_ones() as usize)
+            .sum()
+    }
+
+    /// For each needle orientation (in construction order), a board of the
+    /// locations at which it matches, in the same form as `Board::scan`.
+    pub fn scan_boards(&self, haystack: &Board<BoardContent>) -> Vec<Board<bool>> {
+        let masks = self.value_masks(haystack);
+        let mut result = Vec::new();
+        for (_, _, variant) in &self.variants {
+            let dimensions = haystack.dimensions() - variant.dimensions() + Xy::new(1, 1);
+            let Some(matches) = self.variant_matches(variant, &masks, haystack.dimensions())
+            else {
+                result.push(Board { board: Vec::new() });
+                continue;
+            };
+            result.push(Board {
+                board: matches
+                    .iter()
+                    .map(|row| {
+                        (0..dimensions[0] as usize)
+                            .map(|x| row[x / WORD_BITS] & (1 << (x % WORD_BITS)) != 0)
+                            .collect()
+                    })
+                    .collect(),
+            });
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn needle(rows: &[&str]) -> Board<Option<char>> {
+        Board {
+            board: rows
+                .iter()
+                .map(|r| r.chars().map(|c| if c == '?' { None } else { Some(c) }).collect())
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_symmetry_apply() {
+        let b: Board<char> = Board::from_strings(&["ab", "cd", "ef"]);
+        assert_eq!(Symmetry::Rot90.apply(&b).to_strings(), vec!["eca", "fdb"]);
+        assert_eq!(Symmetry::Rot270.apply(&b).to_strings(), vec!["bdf", "ace"]);
+        assert_eq!(Symmetry::Rot180.apply(&b).to_strings(), vec!["fe", "dc", "ba"]);
+        assert_eq!(Symmetry::Transpose.apply(&b).to_strings(), vec!["ace", "bdf"]);
+        assert_eq!(Symmetry::AntiTranspose.apply(&b).to_strings(), vec!["fdb", "eca"]);
+    }
+
+    #[test]
+    fn test_empty_needle() {
+        let empty = needle(&[]);
+        assert!(Symmetry::Rot90.apply(&empty) == empty);
+        let haystack: Board<char> = Board::from_strings(&["ab", "cd"]);
+        assert_eq!(PatternSearcher::with_all_symmetries(std::slice::from_ref(&empty)).count(&haystack), 0);
+        let searcher = PatternSearcher::new(&[empty, needle(&["d"])]);
+        assert_eq!(searcher.search(&haystack),
+                   vec![PatternMatch { needle: 1, symmetry: Symmetry::Identity, ul: Xy::new(1, 1) }]);
+        assert_eq!(searcher.scan_boards(&haystack).len(), 1);
+    }
+
+    #[test]
+    fn test_matches_naive_scan() {
+        // Wider than one word, so the cross-word shifts are exercised.
+        let rows: Vec<String> = (0..5)
+            .map(|y| (0..150).map(|x| if (x * 7 + y * 3) % 5 < 2 { '#' } else { '.' }).collect())
+            .collect();
+        let haystack: Board<char> = Board::from_strings(&rows);
+        let pattern = needle(&["#?.", "?##"]);
+        let fast = PatternSearcher::new(std::slice::from_ref(&pattern)).scan_boards(&haystack);
+        for window in haystack.windows(3, 2) {
+            let naive = window.iter().all(|(xy, c)| match pattern.at(xy) {
+                Some(p) => p == c,
+                None => true,
+            });
+            assert_eq!(fast[0].at(window.origin()), naive, "at {:?}", window.origin());
+        }
+    }
+
+    #[test]
+    fn test_all_symmetries() {
+        let haystack = Board::from_strings(&[
+            "XMAS.",
+            "...A.",
+            "...M.",
+            "...X.",
+        ]);
+        let searcher = PatternSearcher::with_all_symmetries(&[needle(&["XMAS"])]);
+        let matches = searcher.search(&haystack);
+        assert_eq!(matches.len(), 2);
+        assert!(matches.contains(&PatternMatch {
+            needle: 0, symmetry: Symmetry::Identity, ul: Xy::new(0, 0) }));
+        assert!(matches.contains(&PatternMatch {
+            needle: 0, symmetry: Symmetry::Rot270, ul: Xy::new(3, 0) }));
+        // A palindromic needle only appears once per distinct orientation.
+        assert_eq!(PatternSearcher::with_all_symmetries(&[needle(&["A.A"])])
+                   .count(&Board::from_strings(&["A.A"])), 1);
+    }
+}
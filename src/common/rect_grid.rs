@@ -0,0 +1,171 @@
+#![allow(dead_code)]
+//! Rectangle updates on a coordinate-compressed grid.
+//!
+//! When a puzzle applies a list of rectangle commands to a huge grid, only
+//! the rectangles' edges matter:  between consecutive edge coordinates every
+//! cell sees exactly the same commands.  A `RectGrid` keeps one value per
+//! such block, so the work done depends on the number of commands rather
+//! than on the size of the grid.
+//!
+//! As with `Board::update_rect`, rectangles are half-open: `[ul, br)`.
+
+use std::fmt::Debug;
+
+use super::grid_board::{Board, Scalar, Xy};
+
+#[derive(Clone)]
+pub struct RectGrid<BoardContent> {
+    /// Sorted distinct x coordinates of block edges, including the bounds.
+    xs: Vec<Scalar>,
+    /// Sorted distinct y coordinates of block edges, including the bounds.
+    ys: Vec<Scalar>,
+    /// One value per block; block (i, j) covers `[xs[i], xs[i+1]) x [ys[j], ys[j+1])`.
+    blocks: Board<BoardContent>,
+}
+
+impl<BoardContent> RectGrid<BoardContent>
+where BoardContent: Copy + Debug + PartialEq,
+{
+    /// A grid covering `[ul, br)` filled with `default_value`, whose blocks
+    /// are split along the edges of every rectangle in `rects`.  Only those
+    /// rectangles (or unions of the resulting blocks) may later be updated.
+    /// Panics if the grid is empty, so that there is always a block.
+    pub fn new(ul: Xy, br: Xy,
+               rects: impl IntoIterator<Item = (Xy, Xy)>,
+               default_value: BoardContent) -> Self {
+        assert!(ul[0] < br[0] && ul[1] < br[1], "Grid {:?}-{:?} is empty", ul, br);
+        let mut xs = vec![ul[0], br[0]];
+        let mut ys = vec![ul[1], br[1]];
+        for (rect_ul, rect_br) in rects {
+            assert!(rect_ul[0] >= ul[0] && rect_ul[1] >= ul[1]
+                    && rect_br[0] <= br[0] && rect_br[1] <= br[1],
+                    "Rectangle {:?}-{:?} is outside of the grid", rect_ul, rect_br);
+            xs.extend([rect_ul[0], rect_br[0]]);
+            ys.extend([rect_ul[1], rect_br[1]]);
+        }
+        xs.sort();
+        xs.dedup();
+        ys.sort();
+        ys.dedup();
+        let blocks = Board::new(ys.len() - 1, xs.len() - 1, default_value);
+        RectGrid { xs, ys, blocks }
+    }
+
+    pub fn ul(&self) -> Xy {
+        Xy::new(self.xs[0], self.ys[0])
+    }
+
+    pub fn br(&self) -> Xy {
+        Xy::new(*self.xs.last().unwrap(), *self.ys.last().unwrap())
+    }
+
+    /// The number of compressed blocks.
+    pub fn block_count(&self) -> usize {
+        self.blocks.width() * self.blocks.height()
+    }
+
+    fn edge_index(edges: &[Scalar], coord: Scalar) -> usize {
+        edges.binary_search(&coord).unwrap_or_else(|_| {
+            panic!("{} is not a block edge; include its rectangle when building the grid", coord)
+        })
+    }
+
+    /// Replace every cell `c` in `[ul, br)` with `f(c)`.  This visits each
+    /// covered block, so with N rectangles an update costs up to O(N²) and
+    /// applying all of them O(N³).
+    pub fn update_rect(&mut self, ul: Xy, br: Xy, f: impl Fn(BoardContent) -> BoardContent) {
+        let block_ul = Xy::new(Self::edge_index(&self.xs, ul[0]) as Scalar,
+                               Self::edge_index(&self.ys, ul[1]) as Scalar);
+        let block_br = Xy::new(Self::edge_index(&self.xs, br[0]) as Scalar,
+                               Self::edge_index(&self.ys, br[1]) as Scalar);
+        self.blocks.update_rect(block_ul, block_br, f);
+    }
+
+    pub fn fill_rect(&mut self, ul: Xy, br: Xy, c: BoardContent) {
+        self.update_rect(ul, br, |_| c);
+    }
+
+    /// The value of the cell at `xy`.
+    pub fn at(&self, xy: Xy) -> BoardContent {
+        assert!(xy[0] >= self.ul()[0] && xy[0] < self.br()[0]
+                && xy[1] >= self.ul()[1] && xy[1] < self.br()[1]);
+        let block = |edges: &[Scalar], coord| edges.partition_point(|&e| e <= coord) - 1;
+        self.blocks.at(Xy::new(block(&self.xs, xy[0]) as Scalar, block(&self.ys, xy[1]) as Scalar))
+    }
+
+    /// Every block as (upper left, lower right, value).
+    pub fn blocks(&self) -> impl Iterator<Item = (Xy, Xy, BoardContent)> + '_ {
+        self.blocks.iter().map(|(ij, c)| {
+            let (i, j) = (ij[0] as usize, ij[1] as usize);
+            (Xy::new(self.xs[i], self.ys[j]), Xy::new(self.xs[i + 1], self.ys[j + 1]), c)
+        })
+    }
+
+    /// The sum over all cells of `weight(cell)`.
+    pub fn weighted_sum(&self, weight: impl Fn(BoardContent) -> i64) -> i64 {
+        self.blocks()
+            .map(|(ul, br, c)| {
+                let size = br - ul;
+                size[0] * size[1] * weight(c)
+            })
+            .sum()
+    }
+
+    /// The number of cells satisfying `pred`.
+    pub fn total_area(&self, pred: impl Fn(BoardContent) -> bool) -> i64 {
+        self.weighted_sum(|c| pred(c) as i64)
+    }
+
+    /// Expand to a full board, with the grid's upper left corner at (0, 0).
+    pub fn to_board(&self) -> Board<BoardContent> {
+        let size = self.br() - self.ul();
+        let mut result = Board::new(size[1] as usize, size[0] as usize, self.blocks.at(Xy::new(0, 0)));
+        for (ul, br, c) in self.blocks() {
+            result.fill_rect(ul - self.ul(), br - self.ul(), c);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_board() {
+        let commands = [
+            (Xy::new(1, 1), Xy::new(5, 4)),
+            (Xy::new(3, 0), Xy::new(8, 2)),
+            (Xy::new(0, 3), Xy::new(8, 6)),
+        ];
+        let mut grid = RectGrid::new(Xy::new(0, 0), Xy::new(8, 6), commands, 0i64);
+        let mut board = Board::new(6, 8, 0i64);
+        for (i, &(ul, br)) in commands.iter().enumerate() {
+            let f = |c: i64| if i == 1 { -c + 1 } else { c + 2 };
+            grid.update_rect(ul, br, f);
+            board.update_rect(ul, br, f);
+        }
+        assert!(grid.to_board() == board);
+        assert_eq!(grid.at(Xy::new(4, 3)), board.at(Xy::new(4, 3)));
+        let board_sum: i64 = board.iter().map(|(_, c)| c).sum();
+        assert_eq!(grid.weighted_sum(|c| c), board_sum);
+        assert_eq!(grid.total_area(|c| c > 0) as usize,
+                   board.iter().filter(|&(_, c)| c > 0).count());
+        assert!(grid.block_count() < 48);
+    }
+
+    #[test]
+    fn test_scales_with_commands() {
+        let rect = (Xy::new(0, 0), Xy::new(1_000_000_000, 1_000_000_000));
+        let mut grid = RectGrid::new(rect.0, rect.1, [rect], false);
+        grid.fill_rect(rect.0, rect.1, true);
+        assert_eq!(grid.block_count(), 1);
+        assert_eq!(grid.total_area(|c| c), 1_000_000_000_000_000_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "is empty")]
+    fn test_empty_grid() {
+        RectGrid::new(Xy::new(3, 0), Xy::new(3, 5), [], 0);
+    }
+}
@@ -0,0 +1,190 @@
+#![allow(dead_code)]
+//! Disjoint sets of `0..n`, and Kruskal's algorithm on top of them.
+//!
+//! `UnionFind` keeps each set as a tree of parent links.  Finding a root
+//! compresses the path to it and unions hang the smaller tree under the
+//! larger, so both are effectively constant time.
+
+/// A partition of `0..n` into disjoint sets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    /// The number of elements in each root's set; meaningless for non-roots.
+    size: Vec<usize>,
+    components: usize,
+}
+
+impl UnionFind {
+    /// `n` singleton sets.
+    pub fn new(n: usize) -> Self {
+        UnionFind { parent: (0..n).collect(), size: vec![1; n], components: n }
+    }
+
+    /// The number of elements.
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// The representative of `x`'s set.
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut current = x;
+        while self.parent[current] != root {
+            let next = self.parent[current];
+            self.parent[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    /// Merge the sets of `a` and `b`.  Returns false if they were already
+    /// the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.components -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// The number of elements in `x`'s set.
+    pub fn component_size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// The number of disjoint sets.
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// Whether everything is in one set.
+    pub fn is_connected(&self) -> bool {
+        self.components <= 1
+    }
+
+    /// The size of every set, largest first.
+    pub fn component_sizes(&self) -> Vec<usize> {
+        let mut sizes: Vec<usize> = (0..self.len())
+            .filter(|&x| self.parent[x] == x)
+            .map(|root| self.size[root])
+            .collect();
+        sizes.sort_by(|a, b| b.cmp(a));
+        sizes
+    }
+
+    /// The members of every set, each in increasing order, largest set
+    /// first.
+    pub fn components(&mut self) -> Vec<Vec<usize>> {
+        let mut by_root: Vec<Vec<usize>> = vec![Vec::new(); self.len()];
+        for x in 0..self.len() {
+            let root = self.find(x);
+            by_root[root].push(x);
+        }
+        by_root.retain(|members| !members.is_empty());
+        by_root.sort_by_key(|members| std::cmp::Reverse(members.len()));
+        by_root
+    }
+}
+
+/// What Kruskal's algorithm did with its edges.
+#[derive(Debug, Clone)]
+pub struct Kruskal<CostType> {
+    pub sets: UnionFind,
+    /// The edges that merged two sets, in the order they were taken.
+    pub tree: Vec<(usize, usize, CostType)>,
+    /// How many edges were looked at, merging or not.
+    pub considered: usize,
+}
+
+impl<CostType: Copy> Kruskal<CostType> {
+    /// The last edge that merged two sets; for a spanning tree, the one
+    /// that finally connected everything.
+    pub fn last_merge(&self) -> Option<(usize, usize, CostType)> {
+        self.tree.last().copied()
+    }
+
+    pub fn total_cost(&self) -> CostType
+    where CostType: std::iter::Sum {
+        self.tree.iter().map(|&(_, _, cost)| cost).sum()
+    }
+}
+
+/// Kruskal's algorithm over nodes `0..n`: take `edges`, which must be
+/// sorted by cost, cheapest first, and join the sets of every edge's ends.
+/// Stops once everything is connected, giving a minimum spanning tree, or
+/// after considering `max_edges` edges, giving a clustering.
+pub fn kruskal<CostType: Copy + Ord>(n: usize, edges: &[(usize, usize, CostType)],
+                                     max_edges: Option<usize>) -> Kruskal<CostType> {
+    debug_assert!(edges.windows(2).all(|w| w[0].2 <= w[1].2), "Edges must be sorted by cost");
+    let mut sets = UnionFind::new(n);
+    let mut tree = Vec::new();
+    let mut considered = 0;
+    for &(from, to, cost) in edges {
+        if sets.is_connected() || max_edges.is_some_and(|max| considered >= max) {
+            break;
+        }
+        considered += 1;
+        if sets.union(from, to) {
+            tree.push((from, to, cost));
+        }
+    }
+    Kruskal { sets, tree, considered }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut sets = UnionFind::new(6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert!(sets.same(0, 3) && !sets.same(0, 4));
+        assert_eq!(sets.component_size(2), 4);
+        assert_eq!(sets.component_count(), 3);
+        assert_eq!(sets.component_sizes(), vec![4, 1, 1]);
+        assert_eq!(sets.components(), vec![vec![0, 1, 2, 3], vec![4], vec![5]]);
+        assert!(!sets.is_connected());
+    }
+
+    #[test]
+    fn test_kruskal() {
+        // A square with one diagonal, plus a far-off pair.
+        let edges = [(0, 1, 1), (1, 2, 2), (0, 2, 3), (2, 3, 4), (3, 0, 5), (4, 5, 6), (0, 4, 10)];
+        let clusters = kruskal(6, &edges, Some(3));
+        assert_eq!(clusters.considered, 3);
+        assert_eq!(clusters.tree.len(), 2);
+        assert_eq!(clusters.sets.component_sizes(), vec![3, 1, 1, 1]);
+
+        let tree = kruskal(6, &edges, None);
+        assert!(tree.sets.is_connected());
+        assert_eq!(tree.total_cost(), 23);
+        assert_eq!(tree.last_merge(), Some((0, 4, 10)));
+        assert_eq!(tree.considered, 7);
+
+        let partial = kruskal(6, &edges[..5], None);
+        assert_eq!(partial.last_merge(), Some((2, 3, 4)));
+        assert_eq!(partial.sets.component_count(), 3);
+    }
+}
@@ -0,0 +1,422 @@
+#![allow(dead_code)]
+//! Three-dimensional grids.
+//!
+//! `Xyz` is the 3-D counterpart of `Xy`.  Voxels can be held densely in a
+//! `VoxelBoard`, which is addressed like a `Board`, or sparsely in a
+//! `SparseVoxels` set of occupied cells for shapes scattered through a large
+//! volume.  Both support the usual puzzle questions:  surface area, the
+//! exterior reachable from outside, and 2-D slices.
+//!
+//! Bounding boxes are half-open, `[lo, hi)`, like `xy_range`.
+
+use std::collections::{HashSet, VecDeque};
+use std::fmt::Debug;
+
+use nalgebra::Vector3;
+
+use super::grid_board::{Board, Scalar, Xy};
+
+pub type Xyz = Vector3<Scalar>;
+
+/// The squared Euclidean length of `xyz`, which is exact for integer
+/// coordinates.
+pub fn magnitude_squared(xyz: Xyz) -> Scalar {
+    xyz.dot(&xyz)
+}
+
+/// The Manhattan length of `xyz`.
+pub fn manhattan(xyz: Xyz) -> Scalar {
+    xyz.x.abs() + xyz.y.abs() + xyz.z.abs()
+}
+
+/// Every point in `[lo, hi)`, x varying fastest.
+pub fn xyz_range(lo: Xyz, hi: Xyz) -> impl Iterator<Item = Xyz> {
+    (lo.z..hi.z).flat_map(move |z| {
+        (lo.y..hi.y).flat_map(move |y| (lo.x..hi.x).map(move |x| Xyz::new(x, y, z)))
+    })
+}
+
+/// The smallest box `[lo, hi)` containing every point, or `None` if there are
+/// no points.
+pub fn bounding_box(points: impl IntoIterator<Item = Xyz>) -> Option<(Xyz, Xyz)> {
+    let mut points = points.into_iter();
+    let first = points.next()?;
+    let (lo, hi) = points.fold((first, first), |(lo, hi), p| (lo.inf(&p), hi.sup(&p)));
+    Some((lo, hi + Xyz::new(1, 1, 1)))
+}
+
+/// The six face-adjacent offsets.
+pub const NEIGHBORS6: [Xyz; 6] = [
+    Xyz::new(1, 0, 0),
+    Xyz::new(-1, 0, 0),
+    Xyz::new(0, 1, 0),
+    Xyz::new(0, -1, 0),
+    Xyz::new(0, 0, 1),
+    Xyz::new(0, 0, -1),
+];
+
+/// The 26 offsets to every cell touching a cell's faces, edges or corners.
+pub fn neighbors26() -> Vec<Xyz> {
+    xyz_range(Xyz::new(-1, -1, -1), Xyz::new(2, 2, 2))
+        .filter(|&offset| offset != Xyz::zeros())
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    fn index(self) -> usize {
+        match self {
+            Axis::X => 0,
+            Axis::Y => 1,
+            Axis::Z => 2,
+        }
+    }
+
+    /// The two remaining axes, which become a slice's x and y.
+    fn others(self) -> (usize, usize) {
+        match self {
+            Axis::X => (1, 2),
+            Axis::Y => (0, 2),
+            Axis::Z => (0, 1),
+        }
+    }
+}
+
+/// Count the faces of `solid` cells that do not touch another solid cell.
+pub fn surface_area(solid: impl IntoIterator<Item = Xyz>, is_solid: impl Fn(Xyz) -> bool) -> usize {
+    solid
+        .into_iter()
+        .map(|p| NEIGHBORS6.iter().filter(|&&offset| !is_solid(p + offset)).count())
+        .sum()
+}
+
+/// Flood fill the non-solid cells of `[lo, hi)` that are reachable through
+/// faces from the boundary of the box.
+pub fn flood_exterior(lo: Xyz, hi: Xyz, is_solid: impl Fn(Xyz) -> bool) -> HashSet<Xyz> {
+    let inside = |p: Xyz| (0..3).all(|i| p[i] >= lo[i] && p[i] < hi[i]);
+    let on_boundary = |p: Xyz| (0..3).any(|i| p[i] == lo[i] || p[i] == hi[i] - 1);
+    let mut seen: HashSet<Xyz> = HashSet::new();
+    let mut queue: VecDeque<Xyz> = VecDeque::new();
+    for p in xyz_range(lo, hi).filter(|&p| on_boundary(p) && !is_solid(p)) {
+        seen.insert(p);
+        queue.push_back(p);
+    }
+    while let Some(p) = queue.pop_front() {
+        for offset in NEIGHBORS6 {
+            let next = p + offset;
+            if inside(next) && !is_solid(next) && seen.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    seen
+}
+
+/// A dense box of voxels, with one value per cell.  The origin is at (0, 0, 0).
+#[derive(Clone, PartialEq)]
+pub struct VoxelBoard<BoardContent> {
+    dimensions: Xyz,
+    cells: Vec<BoardContent>,
+}
+
+impl<BoardContent> VoxelBoard<BoardContent>
+where BoardContent: Copy + Debug + PartialEq,
+{
+    pub fn new(dimensions: Xyz, default_value: BoardContent) -> Self {
+        assert!(dimensions.iter().all(|&d| d >= 0));
+        let size = (dimensions.x * dimensions.y * dimensions.z) as usize;
+        VoxelBoard { dimensions, cells: vec![default_value; size] }
+    }
+
+    pub fn dimensions(&self) -> Xyz {
+        self.dimensions
+    }
+
+    pub fn contains(&self, xyz: Xyz) -> bool {
+        (0..3).all(|i| xyz[i] >= 0 && xyz[i] < self.dimensions[i])
+    }
+
+    fn index(&self, xyz: Xyz) -> usize {
+        assert!(self.contains(xyz), "{:?} is outside of {:?}", xyz, self.dimensions);
+        (xyz.x + self.dimensions.x * (xyz.y + self.dimensions.y * xyz.z)) as usize
+    }
+
+    pub fn at(&self, xyz: Xyz) -> BoardContent {
+        self.cells[self.index(xyz)]
+    }
+
+    pub fn maybe_at(&self, xyz: Xyz) -> Option<BoardContent> {
+        if self.contains(xyz) { Some(self.at(xyz)) } else { None }
+    }
+
+    pub fn mut_at(&mut self, xyz: Xyz) -> &mut BoardContent {
+        let index = self.index(xyz);
+        &mut self.cells[index]
+    }
+
+    pub fn set_at(&mut self, xyz: Xyz, c: BoardContent) {
+        let index = self.index(xyz);
+        self.cells[index] = c;
+    }
+
+    pub fn maybe_set_at(&mut self, xyz: Xyz, c: BoardContent) {
+        if self.contains(xyz) {
+            self.set_at(xyz, c);
+        }
+    }
+
+    pub fn all_coords(&self) -> impl Iterator<Item = Xyz> {
+        xyz_range(Xyz::zeros(), self.dimensions)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Xyz, BoardContent)> + '_ {
+        self.all_coords().zip(self.cells.iter().copied())
+    }
+
+    pub fn coords_of(&self, target: BoardContent) -> Vec<Xyz> {
+        self.iter().filter(|&(_, c)| c == target).map(|(xyz, _)| xyz).collect()
+    }
+
+    pub fn count(&self, target: BoardContent) -> usize {
+        self.cells.iter().filter(|&&c| c == target).count()
+    }
+
+    /// The contents of the in-bounds face neighbors of `xyz`.
+    pub fn neighbors6(&self, xyz: Xyz) -> Vec<BoardContent> {
+        NEIGHBORS6.iter().filter_map(|&offset| self.maybe_at(xyz + offset)).collect()
+    }
+
+    /// The contents of all in-bounds neighbors of `xyz`.
+    pub fn neighbors26(&self, xyz: Xyz) -> Vec<BoardContent> {
+        neighbors26().into_iter().filter_map(|offset| self.maybe_at(xyz + offset)).collect()
+    }
+
+    /// The plane perpendicular to `axis` at coordinate `at`, as a 2-D board.
+    /// The slice's x and y are the remaining axes in x, y, z order.
+    pub fn slice(&self, axis: Axis, at: Scalar) -> Board<BoardContent> {
+        let (u, v) = axis.others();
+        let mut result = Board::new(self.dimensions[v] as usize, self.dimensions[u] as usize,
+                                    self.cells[0]);
+        for xy in result.all_coords() {
+            let mut xyz = Xyz::zeros();
+            xyz[axis.index()] = at;
+            xyz[u] = xy[0];
+            xyz[v] = xy[1];
+            result.set_at(xy, self.at(xyz));
+        }
+        result
+    }
+
+    /// Faces of cells matching `is_solid` that touch a non-solid cell or the
+    /// outside of the board.
+    pub fn surface_area(&self, is_solid: impl Fn(BoardContent) -> bool) -> usize {
+        let solid = |xyz| self.maybe_at(xyz).is_some_and(&is_solid);
+        surface_area(self.all_coords().filter(|&xyz| solid(xyz)), solid)
+    }
+
+    /// A mask of the non-solid cells reachable from outside the board.
+    pub fn exterior(&self, is_solid: impl Fn(BoardContent) -> bool) -> VoxelBoard<bool> {
+        let mut result = VoxelBoard::new(self.dimensions, false);
+        for xyz in flood_exterior(Xyz::zeros(), self.dimensions, |xyz| is_solid(self.at(xyz))) {
+            result.set_at(xyz, true);
+        }
+        result
+    }
+
+    /// Faces of solid cells that touch the exterior or the outside of the board.
+    pub fn exterior_surface_area(&self, is_solid: impl Fn(BoardContent) -> bool) -> usize {
+        let exterior = self.exterior(&is_solid);
+        self.all_coords()
+            .filter(|&xyz| is_solid(self.at(xyz)))
+            .map(|xyz| NEIGHBORS6.iter()
+                 .filter(|&&offset| exterior.maybe_at(xyz + offset).unwrap_or(true))
+                 .count())
+            .sum()
+    }
+}
+
+impl<BoardContent> VoxelBoard<BoardContent>
+where BoardContent: Copy + Debug + PartialEq + Default,
+{
+    /// Stack 2-D boards along z, the first board at z = 0.
+    pub fn from_slices(slices: &[Board<BoardContent>]) -> Self {
+        let (width, height) = slices.first().map_or((0, 0), |b| (b.width(), b.height()));
+        let dimensions = Xyz::new(width as Scalar, height as Scalar, slices.len() as Scalar);
+        let mut result = VoxelBoard::new(dimensions, BoardContent::default());
+        for (z, slice) in slices.iter().enumerate() {
+            assert_eq!((slice.width(), slice.height()), (width, height), "Slices differ in size");
+            for (xy, c) in slice.iter() {
+                result.set_at(Xyz::new(xy[0], xy[1], z as Scalar), c);
+            }
+        }
+        result
+    }
+}
+
+/// A sparse set of occupied voxels, unbounded in every direction.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SparseVoxels {
+    cells: HashSet<Xyz>,
+}
+
+impl SparseVoxels {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, xyz: Xyz) -> bool {
+        self.cells.contains(&xyz)
+    }
+
+    /// Add a voxel, returning whether it was newly added.
+    pub fn insert(&mut self, xyz: Xyz) -> bool {
+        self.cells.insert(xyz)
+    }
+
+    pub fn remove(&mut self, xyz: Xyz) -> bool {
+        self.cells.remove(&xyz)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Xyz> + '_ {
+        self.cells.iter().copied()
+    }
+
+    /// The smallest box `[lo, hi)` containing every voxel.
+    pub fn bounding_box(&self) -> Option<(Xyz, Xyz)> {
+        bounding_box(self.iter())
+    }
+
+    /// The number of occupied neighbors of `xyz`, using `offsets` such as
+    /// `NEIGHBORS6` or `neighbors26()`.
+    pub fn count_neighbors(&self, xyz: Xyz, offsets: &[Xyz]) -> usize {
+        offsets.iter().filter(|&&offset| self.contains(xyz + offset)).count()
+    }
+
+    /// Faces of voxels that do not touch another voxel.
+    pub fn surface_area(&self) -> usize {
+        surface_area(self.iter(), |xyz| self.contains(xyz))
+    }
+
+    /// The empty cells reachable from outside the voxels, within the bounding
+    /// box grown by one cell on every side so that the fill can get around
+    /// the shape.
+    pub fn exterior(&self) -> HashSet<Xyz> {
+        match self.bounding_box() {
+            None => HashSet::new(),
+            Some((lo, hi)) => {
+                let margin = Xyz::new(1, 1, 1);
+                flood_exterior(lo - margin, hi + margin, |xyz| self.contains(xyz))
+            }
+        }
+    }
+
+    /// Faces of voxels that touch the exterior, ignoring enclosed pockets.
+    pub fn exterior_surface_area(&self) -> usize {
+        let exterior = self.exterior();
+        surface_area(self.iter(), |xyz| !exterior.contains(&xyz))
+    }
+
+    /// The plane perpendicular to `axis` at coordinate `at`, as a 2-D board
+    /// covering `[lo, hi)` of the remaining axes.  Returns the board and the
+    /// position of its upper left cell.
+    pub fn slice(&self, axis: Axis, at: Scalar) -> Option<(Board<bool>, Xy)> {
+        let (u, v) = axis.others();
+        let points: Vec<Xy> = self.iter()
+            .filter(|xyz| xyz[axis.index()] == at)
+            .map(|xyz| Xy::new(xyz[u], xyz[v]))
+            .collect();
+        let first = *points.first()?;
+        let (ul, br) = points.iter().fold((first, first), |(lo, hi), p| (lo.inf(p), hi.sup(p)));
+        let size = br - ul + Xy::new(1, 1);
+        let mut board = Board::new(size[1] as usize, size[0] as usize, false);
+        for p in points {
+            board.set_at(p - ul, true);
+        }
+        Some((board, ul))
+    }
+}
+
+impl FromIterator<Xyz> for SparseVoxels {
+    fn from_iter<I: IntoIterator<Item = Xyz>>(iter: I) -> Self {
+        SparseVoxels { cells: iter.into_iter().collect() }
+    }
+}
+
+impl<BoardContent> From<&VoxelBoard<BoardContent>> for SparseVoxels
+where BoardContent: Copy + Debug + PartialEq + Into<bool>,
+{
+    fn from(board: &VoxelBoard<BoardContent>) -> Self {
+        board.iter().filter(|&(_, c)| c.into()).map(|(xyz, _)| xyz).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 3x3x3 cube with its center cell hollowed out.
+    fn hollow_cube() -> SparseVoxels {
+        xyz_range(Xyz::zeros(), Xyz::new(3, 3, 3))
+            .filter(|&p| p != Xyz::new(1, 1, 1))
+            .collect()
+    }
+
+    #[test]
+    fn test_basics() {
+        assert_eq!(magnitude_squared(Xyz::new(1, -2, 3)), 14);
+        assert_eq!(manhattan(Xyz::new(1, -2, 3)), 6);
+        assert_eq!(neighbors26().len(), 26);
+        assert_eq!(xyz_range(Xyz::zeros(), Xyz::new(2, 3, 4)).count(), 24);
+        assert_eq!(bounding_box([Xyz::new(1, 5, -1), Xyz::new(3, 2, 0)]),
+                   Some((Xyz::new(1, 2, -1), Xyz::new(4, 6, 1))));
+    }
+
+    #[test]
+    fn test_sparse_surface() {
+        // The small example from 2022 day 18.
+        let two: SparseVoxels = [Xyz::new(1, 1, 1), Xyz::new(2, 1, 1)].into_iter().collect();
+        assert_eq!(two.surface_area(), 10);
+        let cube = hollow_cube();
+        assert_eq!(cube.surface_area(), 54 + 6);
+        assert_eq!(cube.exterior_surface_area(), 54);
+        assert!(!cube.exterior().contains(&Xyz::new(1, 1, 1)));
+        let (slice, ul) = cube.slice(Axis::Z, 1).unwrap();
+        assert_eq!(ul, Xy::new(0, 0));
+        assert_eq!(slice.to_string(), "###\n#.#\n###\n");
+        assert!(cube.slice(Axis::Z, 7).is_none());
+    }
+
+    #[test]
+    fn test_dense() {
+        let mut board = VoxelBoard::new(Xyz::new(3, 3, 3), true);
+        board.set_at(Xyz::new(1, 1, 1), false);
+        assert_eq!(board.count(true), 26);
+        assert_eq!(board.neighbors6(Xyz::new(0, 0, 0)).len(), 3);
+        assert_eq!(board.neighbors26(Xyz::new(1, 1, 1)).len(), 26);
+        assert_eq!(board.surface_area(|c| c), 60);
+        assert_eq!(board.exterior_surface_area(|c| c), 54);
+        assert_eq!(board.exterior(|c| c).count(true), 0);
+        assert_eq!(SparseVoxels::from(&board), hollow_cube());
+
+        let slice = board.slice(Axis::X, 1);
+        assert_eq!(slice.to_string(), "###\n#.#\n###\n");
+        let rebuilt = VoxelBoard::from_slices(&[board.slice(Axis::Z, 0),
+                                                board.slice(Axis::Z, 1),
+                                                board.slice(Axis::Z, 2)]);
+        assert!(rebuilt == board);
+    }
+}
@@ -2,8 +2,10 @@ use std::fmt::Display;
 
 use log::debug;
 
-use crate::common::grid_board::{Board, Xy};
+use crate::common::grid_board::Xy;
 use crate::common::parsing::read_regex_records;
+use crate::common::rect_grid::RectGrid;
+use crate::common::tile::Tile;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 struct LightState {
@@ -34,31 +36,19 @@ impl LightState {
     }
 }
 
-impl From<char> for LightState {
-    fn from(c: char) -> Self {
-        match c {
-            '1' => LightState { on: true, brightness: 1 },
-            '0' => LightState { on: false, brightness: 0 },
-            _ => panic!("Cannot convert {} to LightState", c),
-        }
+impl Tile for LightState {
+    fn to_char(self) -> char {
+        if self.on { '1' } else { '0' }
     }
-}
-impl From<LightState> for char {
-    fn from(val: LightState) -> Self {
-        match val.on {
-            true => '1',
-            false => '0',
+
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            '1' => Some(LightState { on: true, brightness: 1 }),
+            '0' => Some(LightState { on: false, brightness: 0 }),
+            _ => None,
         }
     }
 }
-impl Display for LightState {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let c: char = (*self).into();
-        write!(f, "{}", c)
-    }
-}
-
-type Lights = Board<LightState>;
 
 enum CommandAction {
     TurnOn,
@@ -109,37 +99,31 @@ fn read_input(source: Option<String>) -> Vec<Command> {
 
 pub fn solution(source: Option<String>) -> (i64, i64) {
     let commands = read_input(source);
-    let mut lights = Lights{board: vec![vec![LightState { on: false, brightness: 0 }; 1000]; 1000]};
-    for command in &commands {
+    let rects: Vec<(Xy, Xy)> = commands
+        .iter()
+        .map(|command| (command.from, command.to + Xy::new(1, 1)))
+        .collect();
+    let mut lights = RectGrid::new(
+        Xy::new(0, 0),
+        Xy::new(1000, 1000),
+        rects.iter().copied(),
+        LightState { on: false, brightness: 0 });
+    for (command, &(ul, br)) in commands.iter().zip(&rects) {
         debug!("Executing command: {}", command.to_string());
         match command.action {
             CommandAction::Toggle => {
-                lights.update_rect(
-                    command.from,
-                    command.to + Xy::new(1, 1),
-                    |v: LightState| v.toggle(),
-                );
+                lights.update_rect(ul, br, |v: LightState| v.toggle());
             }
             CommandAction::TurnOff => {
-                lights.update_rect(
-                    command.from,
-                    command.to + Xy::new(1, 1),
-                    |v: LightState| v.turn_off(),
-                );
-
+                lights.update_rect(ul, br, |v: LightState| v.turn_off());
             }
             CommandAction::TurnOn => {
-                lights.update_rect(
-                    command.from,
-                    command.to + Xy::new(1, 1),
-                    |v: LightState| v.turn_on(),
-                );
-
+                lights.update_rect(ul, br, |v: LightState| v.turn_on());
             }
         }
     }
-    let count = lights.iter().filter(|&(_, light)| light.on).count() as i64;
-    let brightness: i64 = lights.iter().map(|(_, light)| light.brightness as i64).sum();
+    let count = lights.total_area(|light| light.on);
+    let brightness = lights.weighted_sum(|light| light.brightness as i64);
     (count, brightness)
 }
 
@@ -1,5 +1,6 @@
 use std::{collections::{HashMap, HashSet}, iter};
 
+use crate::common::graph_search;
 use crate::common::parsing::read_lines;
 
 type Elevation = i8;
@@ -63,18 +64,7 @@ fn all_of(map: &Map, elevation: Elevation) -> HashSet<Coords> {
 fn reachable(map: &Map, start: &Coords) -> HashSet<Coords> {
     let adjacency = make_adjacency(map);
     let successors = make_successors(&adjacency);
-    // Simple breadth-first search of the adjacency graph.
-    let mut worklist: Vec<Coords> = vec![*start];
-    let mut visited: HashSet<Coords> = HashSet::new();
-    while let Some(from) = worklist.pop() {
-        if successors.contains_key(&from) {
-            for to in successors[&from].iter() {
-                worklist.push(*to);
-            }
-        }
-        visited.insert(from);
-    }
-    visited
+    graph_search::reachable([*start], |from| successors.get(from).into_iter().flatten().copied())
 }
 
 fn paths(successors: &HashMap<Coords, HashSet<Coords>>,
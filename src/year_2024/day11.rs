@@ -1,24 +1,16 @@
-use std::collections::HashMap;
+use log::debug;
 
+use crate::common::digits::split_in_half;
+use crate::common::multiset_evolution::{Evolver, Multiset};
 use crate::common::parsing::read_all_records;
 
 type Stone = u64;
 
-type StoneMultiset = HashMap<Stone, usize>;
-
-pub fn count_stones(stones: &StoneMultiset) -> usize {
-    stones.values().sum()
-}
-
 pub fn update_stone(stone: Stone) -> Vec<Stone> {
-    let stone_str = format!("{}", stone);
     if stone == 0 {
         vec![1]
-    } else if stone_str.len() % 2 == 0 {
-        let old_len: usize = stone_str.len();
-        let new_len: usize = stone_str.len() / 2;
-        vec![stone_str[0..new_len].parse::<Stone>().unwrap(),
-             stone_str[new_len..old_len].parse::<Stone>().unwrap()]
+    } else if let Some((high, low)) = split_in_half(stone) {
+        vec![high, low]
     } else {
         vec![stone * 2024]
     }
@@ -26,27 +18,17 @@ pub fn update_stone(stone: Stone) -> Vec<Stone> {
 
 pub fn do_blinks(source: Option<String>, num_iterations: usize) -> i64 {
     let starting_configuration = read_all_records(source);
-    let mut starting_stones = StoneMultiset::new();
-    
-    for stone_str in &starting_configuration[0] {
-        let stone: Stone = stone_str.parse().unwrap();
-        *starting_stones.entry(stone).or_default() += 1;
-    }
-    let starting_stones = starting_stones;
-    let mut working_stones: StoneMultiset = starting_stones.clone();
-
-    println!("Starting stones are: {} stones {:?}", count_stones(&working_stones), working_stones);        
-    for i in 0..num_iterations {
-        let mut new_working: HashMap<Stone, usize> = HashMap::new();
-        for (stone, count) in working_stones.iter() {
-            for new_stone in update_stone(*stone) {
-                *new_working.entry(new_stone).or_default() += count;
-            }
-        }
-        working_stones = new_working;
-        println!("After {} iterations: {} stones {:?}", i, count_stones(&working_stones), working_stones);        
+    let starting_stones: Multiset<Stone> = starting_configuration[0]
+        .iter()
+        .map(|stone_str| stone_str.parse().unwrap())
+        .collect();
+
+    let mut evolver = Evolver::new(|&stone: &Stone| update_stone(stone));
+    let evolution = evolver.run(starting_stones, num_iterations).expect("Too many stones");
+    for (i, population) in evolution.populations.iter().enumerate() {
+        debug!("After {} iterations: {} stones", i, population);
     }
-    count_stones(&working_stones).try_into().unwrap()
+    evolution.last.population().try_into().unwrap()
 }
 
 pub fn day11(source: Option<String>) -> i64 {
@@ -1,5 +1,6 @@
 use std::{collections::{HashMap, HashSet}};
 
+use crate::common::graph_search;
 use crate::common::parsing::read_lines;
 
 type Color = char;
@@ -140,21 +141,7 @@ fn all_of(map: &Map, color: Color) -> HashSet<Coords> {
 
 fn reachable(start: &Coords, adjacency: &Adjacency) -> Region {
     let successors = make_successors(adjacency);
-    // Simple breadth-first search of the adjacency graph.
-    let mut worklist: Vec<Coords> = vec![*start];
-    let mut visited: HashSet<Coords> = HashSet::new();
-    while let Some(from) = worklist.pop() {
-        if visited.contains(&from) {
-            continue;
-        }
-        if successors.contains_key(&from) {
-            for to in successors[&from].iter() {
-                worklist.push(*to);
-            }
-        }
-        visited.insert(from);
-    }
-    visited
+    graph_search::reachable([*start], |from| successors.get(from).into_iter().flatten().copied())
 }
 
 fn make_region(map: &Map, start: &Coords) -> Region {
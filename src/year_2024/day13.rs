@@ -1,7 +1,9 @@
-
-use nalgebra::{SMatrix, Vector2};
 use regex::Regex;
 
+use num::Integer;
+
+use crate::common::linear_system::{solve, Solution};
+use crate::common::number_theory::extended_gcd;
 use crate::common::parsing::read_regex_records;
 
 type Scalar = i64;
@@ -33,36 +35,56 @@ fn parse_puzzles(records: Vec<Vec<String>>) -> Vec<Puzzle> {
     result
 }
 
-type Mat2r = SMatrix<f64, 2, 2>;
-
-
 fn puzzle_cost(puzzle: Puzzle) -> Option<i64> {
-    let button_matrix =  // Call this "B"
-        Mat2r::new(
-            puzzle.buttons.0.0 as f64, puzzle.buttons.1.0 as f64,
-            puzzle.buttons.0.1 as f64, puzzle.buttons.1.1 as f64);
-    let prize_vector =  // Call this "p"
-        Vector2::<f64>::new(puzzle.prize.0 as f64, puzzle.prize.1 as f64);
-    let inverse_button_matrix =  // Call this Bi
-        button_matrix.try_inverse()?;
-    // Press buttons b to get prize p:  B * b = p
-    // to get prize p, press buttons b:  b = Bi * p
-    // to price buttons b:  $$$ = (Bi * p)' . [3 1]
-    let required_buttons = inverse_button_matrix * prize_vector;
-
-    let button_matrix = button_matrix.map(|f| f.round() as i64);
-    let required_buttons = required_buttons.map(|f| f.round() as i64);
-    let prize_vector = prize_vector.map(|f| f.round() as i64);
-    let endpoint = button_matrix * required_buttons;
-    println!("To achieve prize {:?} with buttons {:?}, press {:?}",
-             prize_vector, button_matrix, required_buttons);
-    if endpoint == prize_vector {
-        let cost = Vector2::<i64>::new(3, 1);
-        let score = required_buttons.transpose() * cost;
-        Some(score[(0, 0)])    
-    } else {
-        println!("Which misses the prize.");
-        None
+    // Press buttons b to get prize p:  B * b = p, with one row of B per axis.
+    let ((ax, ay), (bx, by)) = puzzle.buttons;
+    let button_matrix = [vec![ax, bx], vec![ay, by]];
+    let prize_vector = [puzzle.prize.0, puzzle.prize.1];
+    let presses = match solve(&button_matrix, &prize_vector) {
+        // Parallel buttons:  one axis says everything the other does.
+        Solution::Infinite { .. } => {
+            if (ax, bx) != (0, 0) {
+                cheapest_presses(ax, bx, puzzle.prize.0)?
+            } else {
+                cheapest_presses(ay, by, puzzle.prize.1)?
+            }
+        }
+        solution => solution.unique_nonnegative_integer()?,
+    };
+    Some(3 * presses[0] + presses[1])
+}
+
+/// The cheapest non-negative `[a, b]` with `a * a_step + b * b_step = target`,
+/// where pressing A costs 3 and B costs 1.
+fn cheapest_presses(a_step: Scalar, b_step: Scalar, target: Scalar) -> Option<Vec<Scalar>> {
+    let exact = |step: Scalar| if step != 0 && target % step == 0 { Some(target / step) } else { None };
+    match (a_step, b_step) {
+        (0, 0) => (target == 0).then(|| vec![0, 0]),
+        // A button that doesn't move is never worth pressing.
+        (0, _) => exact(b_step).filter(|&b| b >= 0).map(|b| vec![0, b]),
+        (_, 0) => exact(a_step).filter(|&a| a >= 0).map(|a| vec![a, 0]),
+        _ => {
+            // Every solution is a = a0 + t * da, b = b0 - t * db.
+            let (g, x, y) = extended_gcd(a_step, b_step);
+            if target % g != 0 {
+                return None;
+            }
+            let (a0, b0) = (x * (target / g), y * (target / g));
+            let (da, db) = (b_step / g, a_step / g);
+            // Both presses must be non-negative, which bounds t.  The buttons
+            // only ever move forwards, so da and db have the same sign.
+            let (low, high) = if da > 0 {
+                (Integer::div_ceil(&-a0, &da), Integer::div_floor(&b0, &db))
+            } else {
+                (Integer::div_ceil(&b0, &db), Integer::div_floor(&-a0, &da))
+            };
+            // The cost is linear in t, so the cheapest is at one end.
+            [low, high]
+                .into_iter()
+                .filter(|_| low <= high)
+                .map(|t| vec![a0 + t * da, b0 - t * db])
+                .min_by_key(|presses| 3 * presses[0] + presses[1])
+        }
     }
 }
 
@@ -95,6 +117,23 @@ pub fn day13b(source: Option<String>) -> i64 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parallel_buttons() {
+        let puzzle = |buttons, prize| Puzzle { buttons, prize };
+        assert_eq!(puzzle_cost(puzzle(((4, 4), (1, 1)), (10, 10))), Some(8));
+        assert_eq!(puzzle_cost(puzzle(((1, 1), (4, 4)), (10, 10))), Some(8));
+        assert_eq!(puzzle_cost(puzzle(((2, 2), (4, 4)), (3, 3))), None);
+        assert_eq!(puzzle_cost(puzzle(((6, 9), (4, 6)), (10, 15))), Some(4));
+        assert_eq!(puzzle_cost(puzzle(((0, 0), (3, 5)), (9, 15))), Some(3));
+        // Part B sized prizes are solved directly, not by counting presses.
+        let far = 10000000000000;
+        assert_eq!(puzzle_cost(puzzle(((4, 4), (1, 1)), (far + 2, far + 2))),
+                   Some(3 * ((far + 2) / 4) + 2));
+        assert_eq!(puzzle_cost(puzzle(((3, 6), (5, 10)), (far, 2 * far))), Some(far / 5));
+        assert_eq!(puzzle_cost(puzzle(((3, 6), (5, 10)), (far + 1, 2 * far + 2))),
+                   Some(3 * 2 + (far + 1 - 6) / 5));
+    }
+
     #[test]
     fn test_example2() {
         assert_eq!(day13(Some("data/2024/day13_example.txt".to_string())), 480);
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use nalgebra::{Matrix1x2, MatrixXx2};
 use regex::Regex;
 
+use crate::common::cycle::brent;
 use crate::common::parsing::read_regex_records;
 
 type IMatrix = MatrixXx2<i64>;
@@ -101,15 +102,17 @@ pub fn day14_generic(source: Option<String>, w: usize, h:usize, iterations: usiz
 pub fn day14b_generic(source: Option<String>, w: usize, h:usize) -> i64 {
     let state_records = read_regex_records(source, state_regex());
     let state = parse_states(state_records);
-    let mut steps = 0;
-    loop {
+    // The robots' positions repeat, so only one period needs to be searched.
+    let cycle = brent(state.clone(), |s| iterate_state(s, w, h, 1));
+    for steps in 0..(cycle.tail + cycle.period) {
         let (final_positions, _) = iterate_state(&state, w, h, steps);
         if check_for_tree(&final_positions) {
             print_positions(&final_positions, w, h);
             println!("After {} steps", steps);
+            return steps as i64;
         }
-        steps += 1;
     }
+    panic!("No tree in {} steps, after which the robots repeat", cycle.tail + cycle.period);
 }
 
 pub fn day14(source: Option<String>) -> i64 {
@@ -1,48 +1,26 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
+use crate::common::astar::{OptimalPaths, Path, Pathfinder};
 use crate::common::parsing::read_lines;
 use crate::common::grid_board::{self, Board, Direction, Scalar, Xy};
+use crate::common::tile::impl_tile;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum BoardContent {
     Wall, Empty
 }
 
-impl TryFrom<char> for BoardContent {
-    type Error = String;
-    
-    fn try_from(c: char) -> Result<Self, Self::Error> {
-        match c {
-            '#' => Ok(BoardContent::Wall),
-            '.' | 'S' | 'E' => Ok(BoardContent::Empty),
-            _ => Err(format!("Illegal character in board string: `{}`", c))
-        }
-    }
-}
+impl_tile!(BoardContent {
+    BoardContent::Wall => '#',
+    BoardContent::Empty => '.' | 'S' | 'E',
+});
 
-impl ToString for BoardContent {
-    fn to_string(&self) -> String {
-        match self {
-            BoardContent::Wall => "#",
-            BoardContent::Empty => ".",
-        }.to_string()
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct State {
     position: Xy,
     facing: Direction
 }
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.position[0].cmp(&other.position[0])
-            .then(self.position[1].cmp(&other.position[1]))
-            .then(self.facing.cmp(&other.facing))
-    }
-}
-
 #[derive(Debug, Clone)]
 struct Puzzle {
     board: Board<BoardContent>,
@@ -85,78 +63,54 @@ impl Puzzle {
         nexts
     }
 
-    pub fn cost_heuristic(&self, state: State) -> Scalar {
+    pub fn cost_heuristic(&self, state: &State) -> Scalar {
         // TODO include rotation error.
-        (state.position - self.end).sum()
-    }
-
-    pub fn final_states(&self) -> HashSet<State> {
-        HashSet::from_iter(Direction::ALL.iter().map(|&d| State{position: self.start, facing: d}))
+        (state.position - self.end).abs().sum()
     }
 
-    // Find a shortest path from the initial state to any final state.
-    pub fn pathfind(&self) -> Vec<State> {
-        self.pathfind_from_to(&self.initial_state(), &self.final_states())
+    pub fn is_final(&self, state: &State) -> bool {
+        state.position == self.end
     }
 
-    // Find a shortest path from `start` to any `end`.
-    pub fn pathfind_from_to(&self, start: &State, end: &HashSet<State>) -> Vec<State> {
-        Vec::new()
+    fn pathfinder(&self) -> Pathfinder<'_, State, Scalar> {
+        Pathfinder::new(|s| self.next_states(s), |s| self.cost_heuristic(s))
     }
-}
-
-struct Pathfinder {
-    puzzle: Puzzle,
-    successors: HashMap<State, HashSet<State>>,
-    cost_remaining: HashMap<State, Scalar>,
-}
-
-impl Pathfinder {
-    fn backtrack(&mut self) {
 
+    // Find a shortest path from the initial state to any final state.
+    pub fn pathfind(&self) -> Option<Path<State, Scalar>> {
+        self.pathfinder().find_path_to([self.initial_state()], |s| self.is_final(s))
     }
 
-    pub fn astar() -> (Vec<State>, Scalar) {
-        // TODO
-        (Vec::new(), 0)
+    // Find every shortest path from the initial state to any final state.
+    pub fn pathfind_all(&self) -> OptimalPaths<State, Scalar> {
+        self.pathfinder().find_all_paths_to([self.initial_state()], |s| self.is_final(s))
     }
 }
 
 pub fn day16(source: Option<String>) -> i64 {
     let puzzle = Puzzle::from_strings(read_lines(source));
-    puzzle.board.dimensions()[0]
+    puzzle.pathfind().expect("No path through the maze").cost
 }
-
 pub fn day16b(source: Option<String>) -> i64 {
-    day16(source)
+    let puzzle = Puzzle::from_strings(read_lines(source));
+    let best_tiles: HashSet<Xy> = puzzle.pathfind_all()
+        .nodes_on_optimal_paths()
+        .into_iter()
+        .map(|s| s.position)
+        .collect();
+    best_tiles.len() as i64
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     #[test]
-    #[ignore = "TODO"]
     fn test_example() {
-        assert_eq!(day16(Some("data/2024/day16_example.txt".to_string())), 10092);
+        assert_eq!(day16(Some("data/2024/day16_example.txt".to_string())), 7036);
     }
 
     #[test]
-    #[ignore = "requires input not in repository"]
-    fn test_test() {
-        assert_eq!(day16(Some("inputs/2024/day16_test.txt".to_string())), 1495147);
-    }
-
-    #[test]
-    #[ignore = "TODO"]
     fn test_example_b() {
-        assert_eq!(day16b(Some("data/2024/day16_example.txt".to_string())), 9021);
-    }
-
-    #[test]
-    #[ignore = "requires input not in repository"]
-    fn test_test_b() {
-        assert_eq!(day16b(Some("inputs/2024/day16_test.txt".to_string())), 1524905);
+        assert_eq!(day16b(Some("data/2024/day16_example.txt".to_string())), 45);
     }
-
-    // B cannot be tested.
 }
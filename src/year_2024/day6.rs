@@ -1,5 +1,8 @@
 use std::collections::HashSet;
 
+use crate::common::cycle::run_until_repeat;
+use crate::common::grid_board::{Board, Direction, Scalar, Xy};
+use crate::common::jump_table::{JumpTable, WalkOutcome};
 use crate::common::parsing::read_lines;
 
 // Occupancy Grid
@@ -40,6 +43,15 @@ impl Facing {
         }
     }
 
+    fn direction(&self) -> Direction {
+        match self {
+            Facing::North => Direction::North,
+            Facing::East => Direction::East,
+            Facing::South => Direction::South,
+            Facing::West => Direction::West,
+        }
+    }
+
     fn offset(&self) -> (i8, i8) {
         match self {
             Facing::North => (0, -1),
@@ -161,23 +173,14 @@ impl GameState {
 /// For a given game state, the set of visited facings/coordinates before exit,
 /// or None if it iterates without bound.
 fn visited_set(start: &GameState) -> Option<HashSet<(Facing, Coords)>> {
-    let mut visited: HashSet<(Facing, Coords)> = HashSet::new();
-    let mut game = start.clone();
-    loop {
-        if visited.contains(&(game.facing, game.position)) {
-            return None;
-        }
-        visited.insert((game.facing, game.position));
-        match game.advance_game_state() {
-            None => {
-                break;
-            }
-            Some(s) => {
-                game = s;
-            }
-        }
+    let trajectory = run_until_repeat(
+        start.clone(),
+        GameState::advance_game_state,
+        |game| (game.facing, game.position));
+    match trajectory.cycle {
+        Some(_) => None,
+        None => Some(trajectory.keys.into_iter().collect()),
     }
-    Some(visited)
 }
 
 pub fn day6(source: Option<String>) -> i64 {
@@ -192,23 +195,20 @@ pub fn day6(source: Option<String>) -> i64 {
 pub fn day6b(source: Option<String>) -> i64 {
     let lines = read_lines(source);
     let game = GameState::from_lines(lines);
-    let possible_obstacle_locs = visited_set(&game).unwrap();
-    let mut looping_obstacle_locs = HashSet::new();
-    for (counter, (_, xy)) in possible_obstacle_locs.iter().enumerate() {
-        let mut altered_game = game.clone();
-        altered_game.grid[xy.1][xy.0] = true;
-        if visited_set(&altered_game).is_none() {
-            println!(
-                "Found loop at {} {}; tested {} of {}",
-                xy.0,
-                xy.1,
-                counter,
-                possible_obstacle_locs.len()
-            );
-            looping_obstacle_locs.insert(xy);
-        }
-    }
-    i64::try_from(looping_obstacle_locs.len()).unwrap()
+    let possible_obstacle_locs: HashSet<Coords> =
+        visited_set(&game).unwrap().into_iter().map(|(_, xy)| xy).collect();
+    // Each candidate obstacle is checked by jumping from turn to turn.
+    let table = JumpTable::new(&Board { board: game.grid.clone() }, |c| c);
+    let start = Xy::new(game.position.0 as Scalar, game.position.1 as Scalar);
+    let looping_obstacle_locs = possible_obstacle_locs
+        .iter()
+        .filter(|&&xy| xy != game.position)
+        .filter(|&&(x, y)| {
+            let patched = table.with_obstacle(Xy::new(x as Scalar, y as Scalar));
+            patched.walk(start, game.facing.direction()) == WalkOutcome::Loops
+        })
+        .count();
+    i64::try_from(looping_obstacle_locs).unwrap()
 }
 
 #[cfg(test)]
@@ -231,7 +231,6 @@ mod tests {
         assert_eq!(day6b(Some("data/2024/day6_example.txt".to_string())), 6);
     }
 
-    // This test is not run as it requires a lot of time.
     #[test]
     #[ignore = "requires input not in repository"]
     fn test_test_b() {
@@ -1,5 +1,6 @@
 use std::collections::{HashMap, HashSet};
 
+use crate::common::grid_board::{Board, Xy};
 use crate::common::parsing::read_lines;
 
 type Channel = char;
@@ -60,26 +61,14 @@ fn find_antinode_pairs(antennas: Vec<(Channel, Coords, Coords)>) -> HashSet<Coor
 
 fn find_antinode_rays(
     antennas: Vec<(Channel, Coords, Coords)>,
-    w: CoordScalar,
-    h: CoordScalar,
+    board: &Board<char>,
 ) -> HashSet<Coords> {
     let mut result = HashSet::new();
     for (_c, (x1, y1), (x2, y2)) in antennas {
-        for i in 0.. {
-            let new_x = x1 + i * (x1 - x2);
-            let new_y = y1 + i * (y1 - y2);
-            if new_x < 0 || new_x >= w || new_y < 0 || new_y >= h {
-                break;
-            }
-            result.insert((new_x, new_y));
-        }
-        for i in 0.. {
-            let new_x = x1 + -i * (x1 - x2);
-            let new_y = y1 + -i * (y1 - y2);
-            if new_x < 0 || new_x >= w || new_y < 0 || new_y >= h {
-                break;
-            }
-            result.insert((new_x, new_y));
+        let start = Xy::new(x1, y1);
+        let step = Xy::new(x1 - x2, y1 - y2);
+        for (xy, _) in board.walk_ray(start, step).chain(board.walk_ray(start, -step)) {
+            result.insert((xy[0], xy[1]));
         }
     }
     result
@@ -102,17 +91,12 @@ pub fn day8(source: Option<String>) -> i64 {
 
 pub fn day8b(source: Option<String>) -> i64 {
     let lines = read_lines(source);
-    let h: CoordScalar = as_coord_scalar(lines.len());
-    let w: CoordScalar = as_coord_scalar(lines[0].len());
+    let board: Board<char> = Board::from_strings(&lines);
     let antennas = find_all_antennas(lines);
     let pairs = find_antenna_pairs(antennas);
-    let linear_antinodes: HashSet<Coords> = find_antinode_rays(pairs, w, h);
-    let filtered_result: HashSet<Coords> = linear_antinodes
-        .iter()
-        .filter(|c| c.0 >= 0 && c.0 < w && c.1 >= 0 && c.1 < h)
-        .copied()
-        .collect();
-    filtered_result.len().try_into().unwrap()
+    // Rays stop at the edge of the board, so every antinode is in bounds.
+    let antinodes: HashSet<Coords> = find_antinode_rays(pairs, &board);
+    antinodes.len().try_into().unwrap()
 }
 
 #[cfg(test)]
@@ -1,3 +1,8 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use log::debug;
+
 use crate::common::parsing::read_lines;
 
 type Scalar = i64;
@@ -5,163 +10,144 @@ type Scalar = i64;
 type Id = Scalar;
 type Length = Scalar;
 
+/// A run of blocks belonging to one file.
 #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
-struct RleItem {
-    id: Option<Id>,
+struct Extent {
+    id: Id,
+    start: Scalar,
     length: Length,
 }
-type RleList = Vec<RleItem>;
-
-fn str_to_rle(source: &str) -> RleList {
-    let mut result = RleList::new();
-    let mut next_id: Id = 0;
-    let mut chars = source.chars();
-    loop {
-        let file_len_opt = chars.next();
-        if let Some(file_len_char) = file_len_opt {
-            result.push(RleItem {
-                id: Some(next_id),
-                length: file_len_char.to_string().parse().unwrap(),
-            });
-            next_id += 1;
-        } else {
-            break;
+
+/// The blocks of a disk, stored as the extents of each file; everything
+/// else is free.  Extents are kept in order of position.
+#[derive(PartialEq, Eq, Clone, Debug)]
+struct DiskMap {
+    extents: Vec<Extent>,
+    size: Scalar,
+}
+
+impl DiskMap {
+    /// Parse the dense format, alternating file and free lengths.
+    fn new(source: &str) -> Self {
+        let mut extents = Vec::new();
+        let mut position: Scalar = 0;
+        for (i, c) in source.trim().chars().enumerate() {
+            let length = c.to_digit(10).expect("Disk map digit") as Length;
+            if i % 2 == 0 && length > 0 {
+                extents.push(Extent { id: (i / 2) as Id, start: position, length });
+            }
+            position += length;
         }
-        let file_len_opt = chars.next();
-        if let Some(file_len_char) = file_len_opt {
-            result.push(RleItem {
-                id: None,
-                length: file_len_char.to_string().parse().unwrap(),
-            });
-        } else {
-            break;
+        DiskMap { extents, size: position }
+    }
+
+    /// The free runs between extents, as (start, length), in order.
+    fn gaps(&self) -> Vec<(Scalar, Length)> {
+        let mut gaps = Vec::new();
+        let mut position: Scalar = 0;
+        for extent in &self.extents {
+            if extent.start > position {
+                gaps.push((position, extent.start - position));
+            }
+            position = extent.start + extent.length;
         }
+        gaps
     }
-    result
-}
 
-fn rle_to_map_str(rle: &RleList) -> String {
-    let mut result = String::new();
-    for RleItem { id, length } in rle {
-        match id {
-            Some(i) => {
-                for _ in 0..*length {
-                    result += &(i % 10).to_string();
-                }
+    /// Move single blocks from the end of the disk into the leftmost free
+    /// block until there are no gaps, splitting files as needed.
+    fn compact_blocks(&mut self) {
+        let mut gaps = self.gaps().into_iter().peekable();
+        let mut current_gap = gaps.next();
+        let mut extents = Vec::new();
+        for file in self.extents.iter().rev() {
+            // Fill gaps from the file's last block backwards; whatever is
+            // left over stays at the start of the file.
+            let mut remaining = file.length;
+            while remaining > 0 {
+                let Some((gap_start, gap_length)) = current_gap.filter(|&(start, _)| start < file.start)
+                else { break };
+                let moved = gap_length.min(remaining);
+                extents.push(Extent { id: file.id, start: gap_start, length: moved });
+                remaining -= moved;
+                current_gap = if moved < gap_length {
+                    Some((gap_start + moved, gap_length - moved))
+                } else {
+                    gaps.next()
+                };
             }
-            None => {
-                for _ in 0..*length {
-                    result += ".";
-                }
+            if remaining > 0 {
+                extents.push(Extent { id: file.id, start: file.start, length: remaining });
             }
         }
+        extents.sort_by_key(|e| e.start);
+        self.extents = extents;
     }
-    result
-}
 
-fn is_compact(rle: &RleList) -> bool {
-    let mut found_empty = false;
-    for item in rle {
-        if item.length > 0 {
-            if item.id.is_none() && item.length > 0 {
-                found_empty = true;
-            } else if found_empty { return false; }
+    /// Move each whole file, highest id first, into the leftmost gap that
+    /// fits it, if that gap is to its left.
+    fn compact_files(&mut self) {
+        // Gap starts, grouped by gap length, so that the leftmost gap of at
+        // least some length is the least of a few heap tops.  Moving a file
+        // only ever shortens a gap, and the space it frees is never used,
+        // as every file still to move is further left.
+        let gaps = self.gaps();
+        let longest = gaps.iter().map(|&(_, length)| length).max().unwrap_or(0);
+        let mut gaps_by_length: Vec<BinaryHeap<Reverse<Scalar>>> = vec![BinaryHeap::new(); longest as usize + 1];
+        for (start, length) in gaps {
+            gaps_by_length[length as usize].push(Reverse(start));
+        }
+        for extent in self.extents.iter_mut().rev() {
+            let best = (extent.length as usize..gaps_by_length.len())
+                .filter_map(|length| gaps_by_length[length].peek().map(|&Reverse(start)| (start, length)))
+                .min();
+            let Some((start, length)) = best.filter(|&(start, _)| start < extent.start) else { continue };
+            gaps_by_length[length].pop();
+            let left_over = length - extent.length as usize;
+            if left_over > 0 {
+                gaps_by_length[left_over].push(Reverse(start + extent.length));
+            }
+            extent.start = start;
         }
+        self.extents.sort_by_key(|e| e.start);
     }
-    true
-}
-
-fn index_of_first_empty_item(rle: &RleList) -> usize {
-    rle.iter().position(|item| item.id.is_none()).unwrap()
-}
 
-fn index_of_last_nonempty_item(rle: &RleList) -> usize {
-    let reverse_pos = rle
-        .iter()
-        .rev()
-        .position(|item| item.id.is_some() && item.length > 0)
-        .unwrap();
-    rle.len() - reverse_pos - 1
-}
-
-fn write_block(rle: &mut RleList, item: &RleItem) {
-    let id = item.id;
-    let mut remaining_length = item.length;
-    let mut insert_index = index_of_first_empty_item(rle);
-    while rle[insert_index].length < remaining_length {
-        rle[insert_index].id = id;
-        remaining_length -= rle[insert_index].length;
-        insert_index = index_of_first_empty_item(rle);
+    /// The sum of each block's position times its file id.
+    fn checksum(&self) -> i64 {
+        self.extents
+            .iter()
+            .map(|e| e.id * (e.start..e.start + e.length).sum::<Scalar>())
+            .sum()
     }
-    if remaining_length == 0 {
-        return;
-    } // Avoid inserting zero-length blocks.
-    rle[insert_index].length -= remaining_length;
-    rle.insert(
-        insert_index,
-        RleItem {
-            id,
-            length: remaining_length,
-        },
-    );
-}
-
-fn move_last_nonempty_block(rle: &mut RleList) {
-    let index = index_of_last_nonempty_item(rle);
-    let item_copy = rle[index];
-    {  // Mutate the item within the list.
-        let mut_item: &mut RleItem = rle.get_mut(index).unwrap();
-        mut_item.id = None;
-    };
-    write_block(rle, &item_copy);
-}
-
-fn try_to_move(rle: &mut RleList, id_to_move: Id) {
-    let item_to_move: &mut RleItem = rle.iter_mut().find(|i| i.id == Some(id_to_move)).unwrap();
-    println!("Item to move: {:?}", item_to_move);
-}
 
-fn score(rle: &RleList) -> i64 {
-    let mut result: i64 = 0;
-    let mut pos: Scalar = 0;
-    for item in rle {
-        for _ in 0..item.length {
-            result += item.id.unwrap_or_default() * pos;
-            pos += 1;
+    /// One character per block: the last digit of its file id, or `.`.
+    fn to_map_str(&self) -> String {
+        let mut result = String::new();
+        let mut position: Scalar = 0;
+        for extent in &self.extents {
+            result += &".".repeat((extent.start - position) as usize);
+            result += &(extent.id % 10).to_string().repeat(extent.length as usize);
+            position = extent.start + extent.length;
         }
+        result += &".".repeat((self.size - position) as usize);
+        result
     }
-    result
 }
 
 pub fn day9(source: Option<String>) -> i64 {
     let lines = read_lines(source);
-    let mut rle = str_to_rle(&lines[0]);
-    let mut counter = 0;
-    while !is_compact(&rle) {
-        counter += 1;
-        if counter % 50 == 0 {
-            println!("{}", rle_to_map_str(&rle));
-        }
-        move_last_nonempty_block(&mut rle);
-    }
-    println!("{}", rle_to_map_str(&rle));
-    score(&rle)
+    let mut disk = DiskMap::new(&lines[0]);
+    disk.compact_blocks();
+    debug!("{}", disk.to_map_str());
+    disk.checksum()
 }
 
 pub fn day9b(source: Option<String>) -> i64 {
     let lines = read_lines(source);
-    let mut rle = str_to_rle(&lines[0]);
-    let mut counter = 0;
-    let ids: Vec<Id> = rle.iter().filter_map(|i| i.id).rev().collect();
-    for id in ids {
-        counter += 1;
-        if counter % 50 == 0 {
-            println!("{}", rle_to_map_str(&rle));
-        }
-        try_to_move(&mut rle, id)
-    }
-    println!("{}", rle_to_map_str(&rle));
-    score(&rle)
+    let mut disk = DiskMap::new(&lines[0]);
+    disk.compact_files();
+    debug!("{}", disk.to_map_str());
+    disk.checksum()
 }
 
 #[cfg(test)]
@@ -180,14 +166,18 @@ mod tests {
     }
 
     #[test]
-    #[ignore = "not implemented"]
-    fn test_example_b() {
-        assert_eq!(day9b(Some("data/2024/day9_example.txt".to_string())), 2858);
+    fn test_map_str() {
+        let mut disk = DiskMap::new("2333133121414131402");
+        assert_eq!(disk.to_map_str(), "00...111...2...333.44.5555.6666.777.888899");
+        let mut fragmented = disk.clone();
+        fragmented.compact_blocks();
+        assert_eq!(fragmented.to_map_str(), "0099811188827773336446555566..............");
+        disk.compact_files();
+        assert_eq!(disk.to_map_str(), "00992111777.44.333....5555.6666.....8888..");
     }
 
     #[test]
-    #[ignore = "requires input not in repository"]
-    fn test_test_b() {
-        assert_eq!(day9b(Some("inputs/2024/day9_test.txt".to_string())), 1182);
+    fn test_example_b() {
+        assert_eq!(day9b(Some("data/2024/day9_example.txt".to_string())), 2858);
     }
 }
@@ -13,7 +13,7 @@ mod day12;
 mod day13;
 mod day14;
 mod day15;
-//mod day16;
+mod day16;
 mod day17;
 
 type SolutionFn = fn(Option<String>) -> i64;
@@ -48,8 +48,8 @@ const NAME_TO_FN : &[(&str, SolutionFn)] = &[
     ("day14b", day14::day14b),
     ("day15a", day15::day15),
     ("day15b", day15::day15b),
-    //("day16a", day16::day16),
-    //("day16b", day16::day16b),
+    ("day16a", day16::day16),
+    ("day16b", day16::day16b),
     // ("day17a", day17::day17),
     ("day17b", day17::day17b),
 ];
@@ -2,6 +2,7 @@ use regex::Regex;
 
 use log::debug;
 
+use crate::common::digits::repeated_block_numbers;
 use crate::common::parsing::read_lines;
 
 type Id = usize;
@@ -38,28 +39,11 @@ pub fn day2(source: Option<String>) -> (i64, i64) {
     let mut repeat_accumulator: i64 = 0;
     let mut exactly_two_accumulator: i64 = 0;
     for record in records {
-        for id in record.start..=record.end {
-            let id_str = id.to_string();
-            let mut accept = false;
-            for substr_len in 1..=id_str.len() / 2 {
-                let num_repeats = id_str.len() / substr_len;
-                if id_str.len() != num_repeats * substr_len { continue; }
-                let pattern = &id_str[0..substr_len];
-                let mut ok = true;
-                for i in 0..num_repeats {
-                    if id_str[substr_len*i..substr_len*(i+1)] != *pattern { ok=false; }
-                }
-                if ok { 
-                    accept = true;
-                    debug!("Accepted: {} as {} x {}", id, num_repeats, pattern);
-                    if num_repeats==2 { exactly_two_accumulator += id as i64; }
-                }
-            }
-            if accept {
-                debug!("Accepted: {}", id); 
-                repeat_accumulator += id as i64;
-            }
-        }
+        let exactly_two = repeated_block_numbers(record.start..=record.end, |times| times == 2);
+        let repeats = repeated_block_numbers(record.start..=record.end, |times| times >= 2);
+        debug!("Accepted: {:?} of which exactly two: {:?}", repeats, exactly_two);
+        exactly_two_accumulator += exactly_two.iter().sum::<Id>() as i64;
+        repeat_accumulator += repeats.iter().sum::<Id>() as i64;
     }
     (exactly_two_accumulator, repeat_accumulator)
 }
@@ -83,6 +67,7 @@ mod tests {
     const DAY: &str = "2";
     const EXAMPLE_A_DATA: &str = concatcp!("data/2025/day", DAY, "a_example.txt");
     const EXAMPLE_B_DATA: &str = concatcp!("data/2025/day", DAY, "a_example.txt");
+    const OVERLAP_DATA: &str = concatcp!("data/2025/day", DAY, "_overlap_example.txt");
     const INPUT_A_DATA: &str = concatcp!("inputs/2025/day", DAY, "_test.txt");
     const INPUT_B_DATA: &str = concatcp!("inputs/2025/day", DAY, "_test.txt");
 
@@ -105,6 +90,12 @@ mod tests {
         assert_eq!(solution_b(Some(EXAMPLE_B_DATA.to_string())), 4174379265);
     }
 
+    #[test]
+    fn test_overlapping_ranges() {
+        // An ID in two ranges counts once per range.
+        assert_eq!(day2(Some(OVERLAP_DATA.to_string())), (198, 420));
+    }
+
     #[test]
     fn test_test_1b() {
         if File::open(INPUT_B_DATA).is_err() {
@@ -1,7 +1,8 @@
 use log::debug;
 
 use crate::common::parsing::read_lines;
-use crate::common::grid_board::{Board, Xy};
+use crate::common::automaton::{Automaton, LifeRule};
+use crate::common::grid_board::Board;
 
 fn read_input(source: Option<String>) -> Board<char> {
     Board::from_strings(&read_lines(source))
@@ -9,30 +10,15 @@ fn read_input(source: Option<String>) -> Board<char> {
 
 fn solutions(source: Option<String>) -> (i64, i64) {
     let mut board = read_input(source);
-    let mut first_step_removals: i64 = 0;
-    let mut total_removals: i64 = 0;
-    let mut removals: Vec<Xy> = Vec::new();
-    loop {
-        for coord in board.all_coords() {
-            if board.at(coord) != '@' {continue;}
-            let neighbors = board.neighbors8(coord);
-            let occupied_count = neighbors.iter().filter(|c| **c == '@').count();
-            if occupied_count < 4 {
-                debug!("Location at {:?} is free with {} occupied neighbors", coord, occupied_count);
-                total_removals += 1; 
-                removals.push(coord);
-            }
-        }
-        if first_step_removals == 0 {
-            first_step_removals = total_removals;
-        }
-        if removals.is_empty() {
-            break;
-        }
-        for coord in removals.drain(..) {
-            board.set_at(coord, '_');
-        }
-    }
+    // A roll is removed when fewer than four of its neighbors are rolls; nothing is ever added.
+    let removal = LifeRule::from_notation("B/S45678", '@', '.');
+    let automaton = Automaton::new(vec![Box::new(removal)]);
+    let max_steps = board.width() * board.height() + 1;
+    let removals_per_step = automaton.run_until_fixpoint(&mut board, max_steps)
+        .expect("Removal only shrinks the board, so it must settle");
+    debug!("Removals per step: {:?}", removals_per_step);
+    let first_step_removals = removals_per_step.first().copied().unwrap_or(0) as i64;
+    let total_removals = removals_per_step.iter().sum::<usize>() as i64;
     (first_step_removals, total_removals)
 }
 
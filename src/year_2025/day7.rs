@@ -1,8 +1,10 @@
 
 use log::debug;
 
+use crate::common::automaton::{Automaton, RewriteRule};
 use crate::common::grid_board::{self, Xy};
 use crate::common::parsing::read_grid_board;
+use crate::common::tile::impl_tile;
 
 
 
@@ -14,32 +16,12 @@ enum Cell {
     Beam,
 }
 
-impl TryInto<char> for Cell {
-    fn try_into(self) -> Result<char, Self::Error> {
-        match self {
-            Cell::Empty => Ok('.'),
-            Cell::Start => Ok('S'),
-            Cell::Splitter => Ok('^'),
-            Cell::Beam => Ok('|'),
-        }
-    }
-    
-    type Error = (); // No error possible in this conversion
-}
-
-impl TryFrom<char> for Cell {
-    fn try_from(c: char) -> Result<Self, Self::Error> {
-        match c {
-            '.' => Ok(Cell::Empty),
-            'S' => Ok(Cell::Start),
-            '^' => Ok(Cell::Splitter),
-            '|' => Ok(Cell::Beam),
-            _ => Err(()),
-        }
-    }
-
-    type Error = ();
-}
+impl_tile!(Cell {
+    Cell::Empty => '.',
+    Cell::Start => 'S',
+    Cell::Splitter => '^',
+    Cell::Beam => '|',
+});
 
 type Board = grid_board::Board<Cell>;
 
@@ -49,47 +31,39 @@ fn read_input(source: Option<String>) -> Board {
     board
 }
 
-fn iterate(board: &Board) -> Board {
-    let start_kernel = Board::opt_from_strs(&vec![
+fn beam_automaton() -> Automaton<Cell> {
+    let start = RewriteRule::from_strs(&[
         "S",
         ".",
-    ]);
-    let start_result = Board::opt_from_strs(&vec![
+    ], &[
         "S",
         "|",
     ]);
-    let split_kernel = Board::opt_from_strs(&vec![
+    let split = RewriteRule::from_strs(&[
         "_|_",
         "_^_",
-    ]);
-    let split_result = Board::opt_from_strs(&vec![
+    ], &[
         "_|_",
         "|^|",
     ]);
-    let extend_kernel = Board::opt_from_strs(&vec![
+    let extend = RewriteRule::from_strs(&[
         "|",
         ".",
-    ]);
-    let extend_result = Board::opt_from_strs(&vec![
+    ], &[
         "|",
         "|",
     ]);
-    board
-        .transform(&start_kernel, &start_result)
-        .transform(&split_kernel, &split_result)
-        .transform(&extend_kernel, &extend_result)
+    Automaton::new(vec![Box::new(start), Box::new(split), Box::new(extend)])
 }
 
 fn iterate_until_stable(board: &Board) -> Board {
     let mut board = board.clone();
-    loop {
-        let new_board = iterate(&board);
-        if new_board == board {
-            debug!("DONE");
-            return new_board;
-        }
-        board = new_board;
-    }
+    // Every step that changes something adds beam to at least one cell.
+    let max_steps = board.width() * board.height() + 1;
+    let changes = beam_automaton().run_until_fixpoint(&mut board, max_steps)
+        .expect("Beams kept changing");
+    debug!("DONE after {} steps", changes.len());
+    board
 }
 
 fn solutions(source: Option<String>) -> (i64, i64) {
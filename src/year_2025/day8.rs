@@ -1,14 +1,11 @@
 
-use std::collections::{HashMap, HashSet};
-
 use log::debug;
 
-use nalgebra::Vector3;
 use regex::Regex;
 
 use crate::common::parsing::read_regex_records;
-
-type Xyz = Vector3<i64>;
+use crate::common::union_find::kruskal;
+use crate::common::voxel::{magnitude_squared, Xyz};
 
 fn read_input(source: Option<String>) -> Vec<Xyz> {
     let re = Regex::new(r"(\d+),(\d+),(\d+)").unwrap();
@@ -27,11 +24,6 @@ fn read_input(source: Option<String>) -> Vec<Xyz> {
     result
 }
 
-fn magnitude(xyz: Xyz) -> i64 {
-    // This should be a nalgebra builtin?
-    xyz.x * xyz.x + xyz.y * xyz.y + xyz.z * xyz.z
-}
-
 type ConnectionsList = Vec<(usize, usize, i64)>;
 
 /// Return all of the pairwise connections (from, to, cost) sorted by cost.  `from` < `to`.
@@ -42,7 +34,7 @@ fn compute_distances(points: &[Xyz]) -> ConnectionsList {
         #[allow(clippy::needless_range_loop)]
         for to_idx in (from_idx + 1)..points.len() {
             let to = points[to_idx];
-            let d2 = magnitude(from - to);
+            let d2 = magnitude_squared(from - to);
             result.push((from_idx, to_idx, d2));
         }
     }
@@ -52,101 +44,50 @@ fn compute_distances(points: &[Xyz]) -> ConnectionsList {
     result
 }
 
-/// Make the at most n connections of least cost.  Return the resulting subgraphs, largest to
-/// smallest.
+/// Make the at most n connections of least cost, stopping early if everything is connected.
+/// Return the sizes of the resulting circuits, largest to smallest, and the last connection that
+/// merged two circuits, if any did.
 fn make_n_connections(points: &[Xyz], possible_connections: &ConnectionsList, n: usize)
--> (Vec<HashSet<usize>>, (usize, usize)) {
-    let mut next_subgraph_id: usize = 1;
-    let mut node_to_subgraph_id: HashMap<usize, usize> = HashMap::new();
-    let mut connections_made: usize = 0;
-    let mut last_connection: (usize, usize) = (0, 0);
-    for (f, t, c) in possible_connections {
-        debug!("{} connections so far; Considering connecting from {} to {} (cost {})",
-               connections_made, f, t, c);
-        // Four possibilities:
-        // (1) f and t are in the same subgraph; nothing happens.
-        // (2) f and t are in different subgraphs; merge them.
-        // (3) f and t are both absent; add them as a new subgraph.
-        // (4) Only one is present; add the other to its subgraph.
-        if !node_to_subgraph_id.contains_key(f) && !node_to_subgraph_id.contains_key(t) {
-            let id = next_subgraph_id;
-            next_subgraph_id += 1;
-            node_to_subgraph_id.insert(*f, id);
-            node_to_subgraph_id.insert(*t, id);
-            debug!("Connected new neighbors {} -> {} as graph ID {}", f, t, id);
-        } else if node_to_subgraph_id.contains_key(f) && !node_to_subgraph_id.contains_key(t) {
-            node_to_subgraph_id.insert(*t, node_to_subgraph_id[f]);
-            debug!("Connected new {} to existing {} of graph {}", t, f, node_to_subgraph_id[f]);
-        } else if !node_to_subgraph_id.contains_key(f) && node_to_subgraph_id.contains_key(t) {
-            node_to_subgraph_id.insert(*f, node_to_subgraph_id[t]);
-            debug!("Connected new {} to existing {} of graph {}", f, t, node_to_subgraph_id[t]);
-        } else {
-            debug!("Considering {} (graph {}) -> {} (graph {})", f, node_to_subgraph_id[f], t, node_to_subgraph_id[t]);
-            if node_to_subgraph_id[t] == node_to_subgraph_id[f] {
-                debug!("Discarded {} -> {} as both are in graph {}", f, t, node_to_subgraph_id[t]);
-                // We should `continue` here, if this doesn't count as a connection; the rules are
-                // not clear.
-            } else {
-                // Merge subgraphs:  Relabel all nodes in graph 't' as being in graph 'f'
-                let t_graph = node_to_subgraph_id[t];
-                let f_graph = node_to_subgraph_id[f];
-                debug!("Connection {} -> {} merges graph {} into {}", f, t, t_graph, f_graph);
-                let keys: Vec<usize> = node_to_subgraph_id.keys().copied().collect();
-                for k in keys {
-                    if node_to_subgraph_id[&k] == t_graph {
-                        node_to_subgraph_id.insert(k, f_graph);
-                    }
-                }
-            }
-        }
-        last_connection = (*f, *t);
-        connections_made += 1;
-        if connections_made >= n { break; }
-        if node_to_subgraph_id.len() == points.len() { break; }
-    }
-    let mut result: Vec<HashSet<usize>> = Vec::new();
-    let subgraphs = node_to_subgraph_id;
-    debug!("Found subgraph membership map: {:?}", subgraphs);
-    debug!("Last connection was: {} {:?} -> {} {:?}",
-           last_connection.0, points[last_connection.0],
-           last_connection.1, points[last_connection.1]);
-    for i in 1..next_subgraph_id {
-        let mut subgraph: HashSet<usize> = HashSet::new();
-        for (&point_idx, &subgraph_idx) in &subgraphs {
-            if subgraph_idx == i { subgraph.insert(point_idx); }
-        }
-        result.push(subgraph);
+-> (Vec<usize>, Option<(usize, usize)>) {
+    let mut connections = kruskal(points.len(), possible_connections, Some(n));
+    let last_merge = connections.last_merge().map(|(from, to, _)| (from, to));
+    debug!("Made {} connections, {} of them merging circuits", connections.considered, connections.tree.len());
+    if let Some((from, to)) = last_merge {
+        debug!("Last connection was: {} {:?} -> {} {:?}", from, points[from], to, points[to]);
     }
-    result.sort_by_key(|s| -(s.len() as i64));
-    debug!("result {:?}", result);
-    (result, last_connection)
+    debug!("Circuits: {:?}", connections.sets.components());
+    (connections.sets.component_sizes(), last_merge)
 }
 
-
 pub fn solution_a_example(source: Option<String>) -> i64 {
     let points = &read_input(source);
     let distances = compute_distances(points);
-    let (subgraphs, _) = make_n_connections(points,&distances, 10);
-    let result = (subgraphs.iter().take(3).map(|s| s.len()).product::<usize>()) as i64;
+    let (circuit_sizes, _) = make_n_connections(points,&distances, 10);
+    let result = (circuit_sizes.iter().take(3).product::<usize>()) as i64;
     result
 }
 
 pub fn solution_a(source: Option<String>) -> i64 {
     let points = &read_input(source);
     let distances = compute_distances(points);
-    let (subgraphs, _) = make_n_connections(points, &distances, 1000);
-    let result = (subgraphs.iter().take(3).map(|s| s.len()).product::<usize>()) as i64;
+    let (circuit_sizes, _) = make_n_connections(points, &distances, 1000);
+    let result = (circuit_sizes.iter().take(3).product::<usize>()) as i64;
     result
 }
 
-pub fn solution_b(source: Option<String>) -> i64 {
-    let points = &read_input(source);
+/// The product of the x coordinates of the last two junction boxes joined,
+/// or 0 if there are fewer than two boxes, so nothing is ever joined.
+fn last_connection_product(points: &[Xyz]) -> i64 {
     let distances = compute_distances(points);
     let (_, last_two_points) = make_n_connections(points, &distances, 9999999);
-    
+    let Some(last_two_points) = last_two_points else { return 0 };
     points[last_two_points.0][0] * points[last_two_points.1][0]
 }
 
+pub fn solution_b(source: Option<String>) -> i64 {
+    last_connection_product(&read_input(source))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -161,6 +102,18 @@ mod tests {
     const INPUT_A_DATA: &str = concatcp!("inputs/2025/day", DAY, "_test.txt");
     const INPUT_B_DATA: &str = concatcp!("inputs/2025/day", DAY, "_test.txt");
 
+    #[test]
+    fn test_no_connections() {
+        let points = read_input(Some(EXAMPLE_A_DATA.to_string()));
+        let distances = compute_distances(&points);
+        let (circuit_sizes, last_merge) = make_n_connections(&points, &distances, 0);
+        assert_eq!(circuit_sizes, vec![1; points.len()]);
+        assert_eq!(last_merge, None);
+        assert_eq!(make_n_connections(&points[..1], &ConnectionsList::new(), 10).1, None);
+        assert_eq!(last_connection_product(&points[..1]), 0);
+        assert_eq!(last_connection_product(&[]), 0);
+    }
+
     #[test]
     fn test_example() {
         assert_eq!(solution_a_example(Some(EXAMPLE_A_DATA.to_string())), 40);